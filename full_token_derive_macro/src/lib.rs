@@ -1,25 +1,159 @@
 use darling::{FromDeriveInput, FromField};
 use macro_utils::{FieldData, GenericLoad};
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, Data, DeriveInput};
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, Member, Type};
 
 #[derive(FromDeriveInput, Default)]
 #[darling(default, attributes(token))]
 struct StructOps {
     name: Option<String>,
+    // `TokenKind` 的变体名称
+    kind: Option<String>,
 }
 
 #[derive(FromField, Default)]
 #[darling(default, attributes(token))]
 struct FieldOps {
     name: Option<String>,
+    // 不作为 token 数据，重建时使用 `Default::default()`
+    skip: bool,
+    // 数据缺失或类型不符时使用 `Default::default()`
+    default: bool,
+    // 自定义转换，模块内提供 `into_token_meta(&T)` 与 `from_token_meta(&TokenMeta)`
+    with: Option<syn::Path>,
+}
+
+// 枚举的变体名称保存在此字段中
+const VARIANT_KEY: &str = "variant";
+
+struct Field {
+    ops: FieldOps,
+    // 数据名称，未指定时为字段名或位置
+    key: String,
+    member: Member,
+    binding: Ident,
+    ty: Type,
+}
+
+fn load_fields(fields: &Fields) -> Vec<Field> {
+    macro_utils::load_fields(fields, |ops: FieldOps, _, _, _| ops)
+        .into_iter()
+        .enumerate()
+        .map(
+            |(
+                idx,
+                FieldData {
+                    extra,
+                    ident,
+                    ftype,
+                },
+            )| {
+                let key = match (&extra.name, &ident) {
+                    (Some(name), _) => name.clone(),
+                    (None, Some(ident)) => ident.to_string(),
+                    (None, None) => idx.to_string(),
+                };
+                let member = match ident {
+                    Some(ident) => Member::Named(ident),
+                    None => Member::Unnamed(idx.into()),
+                };
+                Field {
+                    ops: extra,
+                    key,
+                    member,
+                    binding: quote::format_ident!("field_{}", idx),
+                    ty: ftype,
+                }
+            },
+        )
+        .collect()
+}
+
+// 按字段绑定到 `field_N` 的模式
+fn pattern(path: &TokenStream2, fields: &Fields, loaded: &[Field]) -> TokenStream2 {
+    let bindings = loaded.iter().map(|f| &f.binding);
+    match fields {
+        Fields::Named(_) => {
+            let members = loaded.iter().map(|f| &f.member);
+            quote::quote! { #path { #(#members: #bindings),* } }
+        }
+        Fields::Unnamed(_) => quote::quote! { #path ( #(#bindings),* ) },
+        Fields::Unit => quote::quote! { #path },
+    }
+}
+
+// 从 `src` 读取字段并构造
+fn construct(path: &TokenStream2, fields: &Fields, loaded: &[Field]) -> TokenStream2 {
+    let values = loaded.iter().map(|f| {
+        let Field { ops, key, ty, .. } = f;
+        let from = match &ops.with {
+            Some(with) => quote::quote! { #with::from_token_meta },
+            None => quote::quote! { <#ty as crate::lexical::token_trait::FromTokenMeta>::from_token_meta },
+        };
+        if ops.skip {
+            quote::quote! { ::std::default::Default::default() }
+        } else if ops.default {
+            quote::quote! { src.get_data(#key).and_then(|d| #from(&d)).unwrap_or_default() }
+        } else {
+            quote::quote! { #from(&src.get_data(#key)?)? }
+        }
+    });
+    match fields {
+        Fields::Named(_) => {
+            let members = loaded.iter().map(|f| &f.member);
+            quote::quote! { #path { #(#members: #values),* } }
+        }
+        Fields::Unnamed(_) => quote::quote! { #path ( #(#values),* ) },
+        Fields::Unit => quote::quote! { #path },
+    }
+}
+
+// `get_data` 中各字段的分支
+fn data_arms(loaded: &[Field]) -> Vec<TokenStream2> {
+    loaded
+        .iter()
+        .filter(|f| !f.ops.skip)
+        .map(|Field { ops, key, binding, ty, .. }| {
+            let value = match &ops.with {
+                Some(with) => quote::quote! { #with::into_token_meta(#binding) },
+                None => quote::quote! {
+                    <#ty as crate::lexical::token_trait::IntoTokenMeta>::into_token_meta(::std::clone::Clone::clone(#binding))
+                },
+            };
+            quote::quote! { #key => Some(#value), }
+        })
+        .collect()
+}
+
+fn data_names(loaded: &[Field]) -> Vec<&str> {
+    loaded
+        .iter()
+        .filter(|f| !f.ops.skip)
+        .map(|f| f.key.as_str())
+        .collect()
+}
+
+// 字段类型的约束，自定义转换的字段不要求实现转换 trait
+fn type_bound(field: &Field) -> Option<TokenStream2> {
+    let Field { ops, ty, .. } = field;
+    let default = (ops.skip || ops.default).then(|| quote::quote! { ::std::default::Default });
+    let convert = (!ops.skip && ops.with.is_none()).then(|| {
+        quote::quote! { crate::lexical::token_trait::IntoTokenMeta+crate::lexical::token_trait::FromTokenMeta+Clone }
+    });
+    match (default, convert) {
+        (Some(d), Some(c)) => Some(quote::quote! { #ty: #c + #d }),
+        (Some(b), None) | (None, Some(b)) => Some(quote::quote! { #ty: #b }),
+        (None, None) => None,
+    }
 }
 
 #[proc_macro_derive(FullToken, attributes(token))]
 pub fn full_token_macro(input: TokenStream) -> TokenStream {
     let derive_token = parse_macro_input!(input as DeriveInput);
 
-    let StructOps { name } = StructOps::from_derive_input(&derive_token).expect("Bad Struct Info");
+    let StructOps { name, kind } =
+        StructOps::from_derive_input(&derive_token).expect("Bad Struct Info");
 
     let DeriveInput {
         attrs: _a,
@@ -33,85 +167,116 @@ pub fn full_token_macro(input: TokenStream) -> TokenStream {
     let name = if let Some(ident) = name {
         quote::quote! {#ident}
     } else {
-        let name = stringify!(ident);
+        let name = format!("{}", ident);
         quote::quote! {#name}
     };
     // 解析泛型
     let generics_loaded = macro_utils::load_generic(&generics);
-    let field_loaded = if let Data::Struct(f) = data {
-        macro_utils::load_fields(&f.fields, |t: FieldOps, n, _ty, _id| {
-            if let Some(ident) = t.name {
-                quote::quote! {#ident}
-            } else {
-                let ide=if let Some(ide)=n{
-                    ide.clone()
-                }else {
-                    unreachable!("Only accept Named Struct")
-                };
-                 let ide = format!("{}",ide);
-                quote::quote! {#ide}
-            }
-        })
-    } else {
-        unreachable!("Expect a Struct")
-    };
 
     let name_impl = quote::quote! {
         fn name(&self) -> & 'static str{
             #name
         }
     };
-    // 字段名称
-    let all_names = field_loaded.iter().map(
-        |FieldData {
-             extra,
-             ident: _,
-             ftype: _,
-         }| extra.clone(),
-    );
-    // 字段转换代码
-    let get_data = field_loaded.iter().map(
-        |FieldData {
-             extra,
-             ident,
-             ftype,
-         }| {
-            let ident = if let Some(ident) = ident {
-                ident.clone()
-            } else {
-                unreachable!();
-            };
-            quote::quote! {#extra =>  Some(<#ftype as crate::lexical::token_trait::IntoTokenMeta>::into_token_meta(self.#ident.clone())), }
-        },
-    );
-    // 直段类型的约束
-    let mut type_bounds = field_loaded
-        .iter()
-        .map(
-            |FieldData {
-                 extra: _,
-                 ident: _,
-                 ftype,
-             }| {
-                let ftype = ftype.clone();
-                quote::quote! { #ftype:crate::lexical::token_trait::IntoTokenMeta+crate::lexical::token_trait::FromTokenMeta+Clone  }
-            },
-        )
-        .collect::<Vec<_>>();
-
-    let from_dyn=field_loaded.iter().map(|FieldData {
-            extra,
-            ident,
-            ftype,
-        }|
-       { let ident = if let Some(ident) = ident {
-            ident.clone()
-        } else {
-            unreachable!();
-        };
-        quote::quote! { #ident:<#ftype as crate::lexical::token_trait::FromTokenMeta>::from_token_meta(&src.get_data(#extra)?)? }
-    }
-    );
+    // 未指定时使用默认的 `TokenKind::Other`
+    let kind_impl = kind.map(|kind| {
+        let kind = quote::format_ident!("{}", kind);
+        quote::quote! {
+            fn kind(&self) -> crate::lexical::token_trait::TokenKind {
+                crate::lexical::token_trait::TokenKind::#kind
+            }
+        }
+    });
+
+    let mut type_bounds = Vec::new();
+    let (get_data, all_names, from_dyn) = match data {
+        Data::Struct(s) => {
+            let loaded = load_fields(&s.fields);
+            type_bounds.extend(loaded.iter().filter_map(type_bound));
+            let path = quote::quote! {Self};
+            let pattern = pattern(&path, &s.fields, &loaded);
+            let arms = data_arms(&loaded);
+            let names = data_names(&loaded);
+            let construct = construct(&path, &s.fields, &loaded);
+            (
+                quote::quote! {
+                    #[allow(unused_variables)]
+                    let #pattern = self;
+                    match name {
+                        #(#arms)*
+                        _=>None
+                    }
+                },
+                quote::quote! { vec![#(#names),*] },
+                quote::quote! { Some(#construct) },
+            )
+        }
+        // 变体名称作为 `variant` 字段，其余数据为当前变体的字段
+        Data::Enum(e) => {
+            let mut get_data = Vec::new();
+            let mut all_names = Vec::new();
+            let mut from_dyn = Vec::new();
+            for variant in &e.variants {
+                let vident = &variant.ident;
+                let vname = vident.to_string();
+                let loaded = load_fields(&variant.fields);
+                // 字段名称不能与变体名称的字段相同
+                if let Some(field) = loaded.iter().find(|f| !f.ops.skip && f.key == VARIANT_KEY) {
+                    let message = format!(
+                        "field name `{}` is reserved for the variant name of an enum token",
+                        VARIANT_KEY
+                    );
+                    let error = match &field.member {
+                        Member::Named(ident) => syn::Error::new_spanned(ident, message),
+                        Member::Unnamed(_) => syn::Error::new_spanned(&field.ty, message),
+                    };
+                    return error.to_compile_error().into();
+                }
+                type_bounds.extend(loaded.iter().filter_map(type_bound));
+                let path = quote::quote! {Self::#vident};
+                let pattern = pattern(&path, &variant.fields, &loaded);
+                let arms = data_arms(&loaded);
+                let names = data_names(&loaded);
+                let construct = construct(&path, &variant.fields, &loaded);
+                get_data.push(quote::quote! {
+                    #[allow(unused_variables)]
+                    #pattern => match name {
+                        #VARIANT_KEY => Some(crate::lexical::token_trait::TokenMeta::Str(String::from(#vname))),
+                        #(#arms)*
+                        _=>None
+                    },
+                });
+                all_names.push(quote::quote! {
+                    #path {..} => vec![#VARIANT_KEY, #(#names),*],
+                });
+                from_dyn.push(quote::quote! {
+                    #vname => Some(#construct),
+                });
+            }
+            (
+                quote::quote! {
+                    match self {
+                        #(#get_data)*
+                    }
+                },
+                quote::quote! {
+                    match self {
+                        #(#all_names)*
+                    }
+                },
+                quote::quote! {
+                    let variant = <String as crate::lexical::token_trait::FromTokenMeta>::from_token_meta(
+                        &src.get_data(#VARIANT_KEY)?
+                    )?;
+                    match variant.as_str() {
+                        #(#from_dyn)*
+                        _=>None
+                    }
+                },
+            )
+        }
+        Data::Union(_) => panic!("FullToken can not be derived for unions"),
+    };
 
     // 泛型部分
     let GenericLoad {
@@ -128,7 +293,7 @@ pub fn full_token_macro(input: TokenStream) -> TokenStream {
 
     let type_params = type_params
         .into_iter()
-        .map(|t| quote::quote! {#t:crate::lexical::token_trait::IntoTokenMeta+crate::lexical::token_trait::FromTokenMeta});
+        .map(|t| quote::quote! {#t:crate::lexical::token_trait::IntoTokenMeta+crate::lexical::token_trait::FromTokenMeta+'static});
 
     where_clause.append(&mut type_bounds);
     where_clause.extend(type_params);
@@ -143,17 +308,18 @@ pub fn full_token_macro(input: TokenStream) -> TokenStream {
         impl #generics FullToken for #ident #generics #where_clause {
            #name_impl
 
+           #kind_impl
+
+           fn as_any(&self) -> &dyn ::std::any::Any {
+               self
+           }
+
            fn get_data(&self, name: &str) -> Option<crate::lexical::token_trait::TokenMeta> {
-               match name {
-                   #(#get_data)*
-                   _=>None
-               }
+               #get_data
             }
 
             fn get_all_name(&self) -> Vec<&'static str> {
-                vec![
-                    #(#all_names),*
-                ]
+                #all_names
             }
         }
 
@@ -163,11 +329,26 @@ pub fn full_token_macro(input: TokenStream) -> TokenStream {
             }
             fn from_token(src: Box<dyn FullToken>) -> Option<Self>{
                 if src.name()==Self::token_name(){
-                   Some(
-                       Self{
-                           #(#from_dyn),*
-                        }
-                    )
+                    #from_dyn
+                }else{
+                    None
+                }
+            }
+        }
+
+        // 作为其他 token 的字段时保存为嵌套的 token
+        impl #generics crate::lexical::token_trait::IntoTokenMeta for #ident #generics #where_clause{
+            fn into_token_meta(self) -> crate::lexical::token_trait::TokenMeta{
+                crate::lexical::token_trait::TokenMeta::Token(
+                    crate::lexical::token_trait::TokenData::new(&self)
+                )
+            }
+        }
+
+        impl #generics crate::lexical::token_trait::FromTokenMeta for #ident #generics #where_clause{
+            fn from_token_meta(src: &crate::lexical::token_trait::TokenMeta) -> Option<Self>{
+                if let crate::lexical::token_trait::TokenMeta::Token(data) = src{
+                    data.rebuild()
                 }else{
                     None
                 }
@@ -0,0 +1,440 @@
+//! 离线链接检查
+//!
+//! 检查链接与图片指向的本地文件是否存在、`#fragment` 是否对应目标文件中的标题锚点，
+//! 外部链接不访问网络，只列出
+
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    render::safe::url_scheme,
+    syntax::{parse_blocks, toc::Slugger, Node, NodeKind, Options},
+};
+
+/// 问题类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    /// 本地文件不存在
+    MissingFile,
+    /// 目标文件中没有对应的锚点
+    MissingAnchor,
+    /// 维基链接的目标无法解析
+    UnresolvedWiki,
+}
+
+/// 链接问题，`line` 与 `column` 从 1 开始
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkIssue {
+    pub line: usize,
+    pub column: usize,
+    pub destination: String,
+    pub kind: IssueKind,
+    /// 建议替换为的链接
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for LinkIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.kind {
+            IssueKind::MissingFile => "file not found",
+            IssueKind::MissingAnchor => "anchor not found",
+            IssueKind::UnresolvedWiki => "unresolved wiki link",
+        };
+        write!(
+            f,
+            "{}:{}: {} `{}`",
+            self.line, self.column, reason, self.destination
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{}`?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// 外部链接
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalLink {
+    pub line: usize,
+    pub column: usize,
+    pub url: String,
+}
+
+/// 检查结果
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub issues: Vec<LinkIssue>,
+    pub external: Vec<ExternalLink>,
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// 链接检查器，相对链接以 `base` 目录为基准
+#[derive(Debug, Clone)]
+pub struct LinkChecker {
+    base: PathBuf,
+    // 以 `/` 开头的链接的根目录，默认与 `base` 相同
+    root: Option<PathBuf>,
+    opts: Options,
+}
+
+impl LinkChecker {
+    pub fn new<P: Into<PathBuf>>(base: P) -> Self {
+        Self {
+            base: base.into(),
+            root: None,
+            opts: Options::default(),
+        }
+    }
+
+    /// 设置 `/` 开头的链接的根目录
+    pub fn root<P: Into<PathBuf>>(mut self, root: P) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// 设置解析文档使用的选项
+    pub fn options(mut self, opts: Options) -> Self {
+        self.opts = opts;
+        self
+    }
+
+    /// 检查文件，相对链接以文件所在目录为基准
+    pub fn check_file<P: AsRef<Path>>(path: P, opts: Options) -> std::io::Result<Report> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        let base = path.parent().unwrap_or(Path::new("."));
+        Ok(Self::new(base).options(opts).check(&source))
+    }
+
+    /// 检查文档中的全部链接
+    pub fn check(&self, source: &str) -> Report {
+        let lines = source.lines().collect::<Vec<_>>();
+        let blocks = parse_blocks(source, &self.opts);
+        let own = anchors(blocks.iter().map(|(node, _)| node));
+        let mut cache = HashMap::new();
+        let mut report = Report::default();
+
+        for (node, span) in &blocks {
+            let mut links = Vec::new();
+            collect_links(node, &mut links);
+            // 按顺序在块的源码中查找链接目标的位置
+            let mut cursor = (span.start, 0);
+            for link in links {
+                let needle = match &link {
+                    Link::Url(url) | Link::Wiki(url) => url.as_str(),
+                };
+                let (line, column) = locate(&lines, span.clone(), &mut cursor, needle);
+                let destination = match link {
+                    Link::Url(url) => url,
+                    Link::Wiki(target) => {
+                        report.issues.push(LinkIssue {
+                            line,
+                            column,
+                            destination: target,
+                            kind: IssueKind::UnresolvedWiki,
+                            suggestion: None,
+                        });
+                        continue;
+                    }
+                };
+                if url_scheme(&destination).is_some() || destination.starts_with("//") {
+                    report.external.push(ExternalLink {
+                        line,
+                        column,
+                        url: destination,
+                    });
+                    continue;
+                }
+                if let Some((kind, suggestion)) = self.check_local(&destination, &own, &mut cache) {
+                    report.issues.push(LinkIssue {
+                        line,
+                        column,
+                        destination,
+                        kind,
+                        suggestion,
+                    });
+                }
+            }
+        }
+        report
+    }
+
+    /// 检查本地链接，有问题时返回问题类型与建议
+    fn check_local(
+        &self,
+        destination: &str,
+        own: &[String],
+        cache: &mut HashMap<PathBuf, Vec<String>>,
+    ) -> Option<(IssueKind, Option<String>)> {
+        let (path, fragment) = destination.split_once('#').unwrap_or((destination, ""));
+        let path = percent_decode(path.split('?').next().unwrap_or_default());
+        let fragment = percent_decode(fragment);
+
+        let target_anchors = if path.is_empty() {
+            own
+        } else {
+            let file = match path.strip_prefix('/') {
+                Some(rest) => self.root.as_ref().unwrap_or(&self.base).join(rest),
+                None => self.base.join(&path),
+            };
+            if !file.exists() {
+                let suggestion = closest_file(&file).map(|name| {
+                    let dir = path.rfind('/').map(|i| &path[..i + 1]).unwrap_or_default();
+                    format!("{}{}", dir, name)
+                });
+                return Some((IssueKind::MissingFile, suggestion));
+            }
+            if fragment.is_empty() || !is_markdown(&file) {
+                return None;
+            }
+            cache.entry(file).or_insert_with_key(|file| {
+                let source = fs::read_to_string(file).unwrap_or_default();
+                anchors(parse_blocks(&source, &self.opts).iter().map(|(n, _)| n))
+            })
+        };
+
+        if fragment.is_empty() || target_anchors.contains(&fragment) {
+            return None;
+        }
+        let suggestion = closest(&fragment, target_anchors.iter().map(String::as_str))
+            .map(|anchor| format!("{}#{}", path, anchor));
+        Some((IssueKind::MissingAnchor, suggestion))
+    }
+}
+
+enum Link {
+    Url(String),
+    Wiki(String),
+}
+
+fn collect_links(node: &Node, links: &mut Vec<Link>) {
+    match &node.kind {
+        NodeKind::Link { destination, .. } | NodeKind::Image { destination, .. } => {
+            links.push(Link::Url(destination.clone()))
+        }
+        NodeKind::WikiLink {
+            destination: Some(destination),
+            ..
+        } => links.push(Link::Url(destination.clone())),
+        NodeKind::WikiLink { target, .. } => links.push(Link::Wiki(target.clone())),
+        _ => {}
+    }
+    for child in &node.children {
+        collect_links(child, links);
+    }
+}
+
+/// 文档中的锚点：标题生成的锚点与 `{#id}` 指定的 id
+fn anchors<'a, I: Iterator<Item = &'a Node>>(nodes: I) -> Vec<String> {
+    fn walk(node: &Node, slugger: &mut Slugger, out: &mut Vec<String>) {
+        if let Some(id) = &node.attrs.id {
+            out.push(id.clone());
+        } else if let NodeKind::Heading { .. } = node.kind {
+            out.push(slugger.slug(&node.text_content()));
+        }
+        for child in &node.children {
+            walk(child, slugger, out);
+        }
+    }
+    let mut slugger = Slugger::default();
+    let mut out = Vec::new();
+    for node in nodes {
+        walk(node, &mut slugger, &mut out);
+    }
+    out
+}
+
+/// 从 `cursor` 开始在块的源码中查找文本，找不到时使用块的起始位置
+pub(crate) fn locate(
+    lines: &[&str],
+    span: Range<usize>,
+    cursor: &mut (usize, usize),
+    needle: &str,
+) -> (usize, usize) {
+    let end = span.end.min(lines.len());
+    for (idx, line) in lines.iter().enumerate().take(end).skip(cursor.0) {
+        let from = if idx == cursor.0 { cursor.1 } else { 0 };
+        if let Some(pos) = line.get(from..).and_then(|rest| rest.find(needle)) {
+            let pos = from + pos;
+            *cursor = (idx, pos + needle.len());
+            return (idx + 1, line[..pos].chars().count() + 1);
+        }
+    }
+    (span.start + 1, 1)
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| matches!(e.to_ascii_lowercase().as_str(), "md" | "markdown"))
+}
+
+/// 同一目录下名称最接近的文件
+fn closest_file(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let dir = path.parent()?;
+    let entries = fs::read_dir(dir).ok()?;
+    let names = entries
+        .filter_map(|e| e.ok()?.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+    closest(name, names.iter().map(String::as_str)).map(String::from)
+}
+
+/// 编辑距离最小的候选，距离过大时不作为建议
+///
+/// 以 `target` 开头或包含 `target` 的候选（如 `nope` 与 `nope-heading`）不受距离限制
+fn closest<'a, I: Iterator<Item = &'a str>>(target: &str, candidates: I) -> Option<&'a str> {
+    let len = target.chars().count();
+    let limit = (len / 2).max(2);
+    let contains = |c: &str| len >= 3 && c.contains(target);
+    candidates
+        .map(|c| (distance(target, c), c))
+        .filter(|(d, c)| *d <= limit || contains(c))
+        .min_by_key(|(d, c)| (!c.starts_with(target), *d))
+        .map(|(_, c)| c)
+}
+
+/// Levenshtein 编辑距离
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                prev.min(cur).min(row[j]) + 1
+            };
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+/// 解码 `%XX`，无效的序列保持原样
+fn percent_decode(src: &str) -> String {
+    let bytes = src.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(
+            closest("instal", ["install", "usage"].into_iter()),
+            Some("install")
+        );
+        assert_eq!(closest("xyz", ["install"].into_iter()), None);
+        // 以目标开头或包含目标的候选不受距离限制
+        assert_eq!(
+            closest("nope", ["note", "nope-heading"].into_iter()),
+            Some("nope-heading")
+        );
+        assert_eq!(
+            closest("started", ["install", "getting-started"].into_iter()),
+            Some("getting-started")
+        );
+        assert_eq!(closest("ab", ["abcdef"].into_iter()), None);
+        assert_eq!(percent_decode("a%20b%zz"), "a b%zz");
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = std::env::temp_dir().join(format!("md_check_{}", std::process::id()));
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(
+            dir.join("docs/guide.md"),
+            "# Getting Started\n## Install {#setup}\n",
+        )
+        .unwrap();
+
+        let source = "# Intro\n\nsee [guide](docs/guide.md#getting-started), \
+                      [setup](docs/guide.md#setpu)\n\
+                      and [here](#intro) [there](#intr0)\n\n\
+                      > ![img](docs/gide.md) <https://example.com>\n\n\
+                      ## Nope heading\n\n[d](#nope)\n";
+        let report = LinkChecker::new(&dir).check(source);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            report.issues,
+            vec![
+                LinkIssue {
+                    line: 3,
+                    column: 53,
+                    destination: String::from("docs/guide.md#setpu"),
+                    kind: IssueKind::MissingAnchor,
+                    suggestion: Some(String::from("docs/guide.md#setup")),
+                },
+                LinkIssue {
+                    line: 4,
+                    column: 28,
+                    destination: String::from("#intr0"),
+                    kind: IssueKind::MissingAnchor,
+                    suggestion: Some(String::from("#intro")),
+                },
+                LinkIssue {
+                    line: 6,
+                    column: 10,
+                    destination: String::from("docs/gide.md"),
+                    kind: IssueKind::MissingFile,
+                    suggestion: Some(String::from("docs/guide.md")),
+                },
+                LinkIssue {
+                    line: 10,
+                    column: 5,
+                    destination: String::from("#nope"),
+                    kind: IssueKind::MissingAnchor,
+                    suggestion: Some(String::from("#nope-heading")),
+                },
+            ]
+        );
+        assert_eq!(
+            report.issues[1].to_string(),
+            "4:28: anchor not found `#intr0`, did you mean `#intro`?"
+        );
+        assert_eq!(
+            report.external,
+            vec![ExternalLink {
+                line: 6,
+                column: 25,
+                url: String::from("https://example.com"),
+            }]
+        );
+    }
+}
@@ -2,8 +2,12 @@ use std::ops::Not;
 
 use super::{
     super::tokens::{
+        angle::PartAngle,
         code_snippet::PartCodeSnippet,
+        entity::PartEntity,
+        math::PartMath,
         titles::{Title1, Title2, Title3, Title4, Title5, Title6},
+        wiki::PartWiki,
         Trans,
     },
     core::DFA,
@@ -14,8 +18,8 @@ use super::{
 use self::trans_holder::TransHolder;
 
 use crate::lexical::tokens::{
-    BoxEnd, BoxMid, ChangeLine, Idented, ImgStart, LinkStart, NewParam, OrderList, Reference,
-    SepChar, SepLine, Star, UnorderList,
+    BoxEnd, BoxMid, ChangeLine, HardBreak, Idented, ImgStart, LinkStart, NewParam, OrderList,
+    Reference, SepChar, SepLine, Star, UnorderList,
 };
 
 mod trans_holder;
@@ -122,19 +126,45 @@ impl DFABuilder {
                 //######
                 next = h.add_tran_with_auto_next(next, c);
                 h.set_accept_status(next, Title6);
-                //refer
+                //refer `>` 之后可以有一个空格，`> > ` 为二级引用
                 next = h.add_tran_with_auto_next(1, '>');
                 next = h.add_tran(next, '>', next);
+                let refer = next;
+                h.set_accept_status(next, Reference);
+                next = h.add_tran_with_auto_next(refer, ' ');
+                h.add_tran(next, '>', refer);
                 h.set_accept_status(next, Reference);
                 //link start
                 next = h.add_tran_with_auto_next(0, '[');
                 next = h.add_tran(1, '[', next);
                 h.set_accept_status(next, LinkStart);
+                let link_start = next;
                 //image start
                 next = h.add_tran_with_auto_next(0, '!');
                 next = h.add_tran(1, '!', next);
                 next = h.add_tran_with_auto_next(next, '[');
                 h.set_accept_status(next, ImgStart);
+                // 维基链接 `[[…]]` 与嵌入 `![[…]]`，内部不可跨行，也不可出现方括号
+                let wiki = h.add_tran_with_auto_next(link_start, '[');
+                h.add_tran(next, '[', wiki);
+                let inner = h.counter_next();
+                for state in [wiki, inner] {
+                    h.add_can_any(
+                        state,
+                        AdHocCanAny::new(|c| {
+                            (!(AnyType::Char('[') | AnyType::Char(']') | AnyType::Char('\n')))
+                                .type_match(c)
+                        }),
+                    );
+                    h.add_tran(
+                        state,
+                        !(AnyType::Char('[') | AnyType::Char(']') | AnyType::Char('\n')),
+                        inner,
+                    );
+                }
+                next = h.add_tran_with_auto_next(inner, ']');
+                next = h.add_tran_with_auto_next(next, ']');
+                h.set_accept_status(next, PartWiki);
                 //box mid
                 next = h.add_tran_with_auto_next(0, ']');
                 next = h.add_tran_with_auto_next(next, '(');
@@ -147,13 +177,21 @@ impl DFABuilder {
                 let m1 = next;
                 next = h.add_tran_with_auto_next(1, '*');
                 let m2 = next;
+                next = h.add_tran_with_auto_next(1, '+');
+                let m3 = next;
                 h.set_accept_status(m1, UnorderList);
                 h.set_accept_status(m2, UnorderList);
-                // order list
-                h.add_can_any(next, AdHocCanAny::new(|c| AnyType::Digit.type_match(c)));
-                next = h.add_tran_with_auto_next(next, AnyType::Digit);
-                next = h.add_tran_with_auto_next(next, '.');
-                h.set_accept_status(next, OrderList);
+                h.set_accept_status(m3, UnorderList);
+                // order list 1~9 位数字 + `.` | `)`
+                let order_end = h.counter_next();
+                next = 1;
+                for _ in 0..9 {
+                    h.add_can_any(next, AdHocCanAny::new(|c| AnyType::Digit.type_match(c)));
+                    next = h.add_tran_with_auto_next(next, AnyType::Digit);
+                    h.add_tran(next, '.', order_end);
+                    h.add_tran(next, ')', order_end);
+                }
+                h.set_accept_status(order_end, OrderList);
                 // sep line
                 next = h.add_tran_with_auto_next(1, '-');
                 next = h.add_tran_with_auto_next(next, '-');
@@ -176,41 +214,41 @@ impl DFABuilder {
                 h.set_accept_status(next, SepChar);
                 // // line change
                 next = h.add_tran_with_auto_next(0, '\n');
-                next = h.add_tran(1, '\n', next);
                 h.set_accept_status(next, ChangeLine);
-                // // parghe change
+                // // parghe change 空行
+                next = h.add_tran_with_auto_next(1, '\n');
+                h.set_accept_status(next, NewParam);
+                // // hard break 行末两个及以上空格
                 next = h.add_tran_with_auto_next(0, ' ');
                 next = h.add_tran_with_auto_next(next, ' ');
+                next = h.add_tran(next, ' ', next);
                 next = h.add_tran_with_auto_next(next, '\n');
-                h.set_accept_status(next, NewParam);
-                // //idented
-                next = h.add_tran_with_auto_next(1, ' ');
-                next = h.add_tran_with_auto_next(next, ' ');
-                next = h.add_tran_with_auto_next(next, ' ');
-                next = h.add_tran_with_auto_next(next, ' ');
-                next = h.add_tran(1, '\t', next);
+                h.set_accept_status(next, HardBreak);
+                // //idented 行首缩进达到 4 列，制表符对齐到 4 的倍数列
+                let c1 = h.add_tran_with_auto_next(1, ' ');
+                let c2 = h.add_tran_with_auto_next(c1, ' ');
+                let c3 = h.add_tran_with_auto_next(c2, ' ');
+                next = h.add_tran_with_auto_next(c3, ' ');
+                for col in [1, c1, c2, c3] {
+                    h.add_tran(col, '\t', next);
+                }
+                h.add_tran(next, ' ', next);
+                h.add_tran(next, '\t', next);
                 h.set_accept_status(next, Idented);
 
                 // 转义符
                 next = h.add_tran_with_auto_next(0, '\\');
                 next = h.add_tran(1, '\\', next);
                 let ts = next;
-                next = h.add_tran_with_auto_next(next, '\\');
-                next = h.add_tran(ts, '#', next);
-                next = h.add_tran(ts, '`', next);
-                next = h.add_tran(ts, '-', next);
-                next = h.add_tran(ts, '-', next);
-                next = h.add_tran(ts, '{', next);
-                next = h.add_tran(ts, '}', next);
-                next = h.add_tran(ts, '[', next);
-                next = h.add_tran(ts, ']', next);
-                next = h.add_tran(ts, '(', next);
-                next = h.add_tran(ts, ')', next);
-                next = h.add_tran(ts, '+', next);
-                next = h.add_tran(ts, '.', next);
-                next = h.add_tran(ts, '!', next);
-                next = h.add_tran(ts, '|', next);
+                // 全部 ascii 标点均可转义，其余字符前的反斜杠作为普通文本
+                next = h.counter_next();
+                for c in "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~".chars() {
+                    h.add_tran(ts, c, next);
+                }
                 h.set_accept_status(next, Trans);
+                // 反斜杠换行
+                next = h.add_tran_with_auto_next(ts, '\n');
+                h.set_accept_status(next, HardBreak);
 
                 // 简短代码块
                 // 空白内容
@@ -260,6 +298,174 @@ impl DFABuilder {
                 h.add_tran(next, AnyType::Any, inner);
                 next = h.add_tran_with_auto_next(next, '`');
                 h.set_accept_status(next, PartCodeSnippet);
+
+                // 数学公式 与简短代码块相同，内容不处理转义
+                next = h.add_tran_with_auto_next(0, '$');
+                next = h.add_tran(1, '$', next);
+                let dollar = next;
+                // 行内公式 `$` 之后不能是空白或数字
+                h.add_can_any(
+                    dollar,
+                    AdHocCanAny::new(|c| (!(AnyType::WhiteSpace | AnyType::Digit)).type_match(c)),
+                );
+                let inner =
+                    h.add_tran_with_auto_next(dollar, !(AnyType::WhiteSpace | AnyType::Digit));
+                // 结束的 `$` 之前不能是空白
+                let inner_space = h.add_tran_with_auto_next(inner, AnyType::WhiteSpace);
+                h.add_tran(inner, !AnyType::WhiteSpace, inner);
+                h.add_tran(inner_space, AnyType::WhiteSpace, inner_space);
+                h.add_tran(inner_space, !AnyType::WhiteSpace, inner);
+                for state in [inner, inner_space] {
+                    h.add_can_any(
+                        state,
+                        AdHocCanAny::new(|c| {
+                            AnyType::WhiteSpace
+                                .type_match(c)
+                                .or_else(|| (!AnyType::WhiteSpace).type_match(c))
+                        }),
+                    );
+                }
+                next = h.add_tran_with_auto_next(inner, '$');
+                h.set_accept_status(next, PartMath);
+                // 结束的 `$` 之后不能是数字，否则作为内容继续
+                h.add_can_any(next, AdHocCanAny::new(|c| AnyType::Digit.type_match(c)));
+                h.add_tran(next, AnyType::Digit, inner);
+                // 独立公式 `$$…$$`
+                next = h.add_tran_with_auto_next(dollar, '$');
+                h.add_can_any(
+                    next,
+                    AdHocCanAny::new(|c| (!AnyType::Char('$')).type_match(c)),
+                );
+                let inner = h.add_tran_with_auto_next(next, !AnyType::Char('$'));
+                h.add_can_any(
+                    inner,
+                    AdHocCanAny::new(|input| AnyType::Any.type_match(input)),
+                );
+                h.add_tran(inner, AnyType::Any, inner);
+                next = h.add_tran_with_auto_next(inner, '$');
+                h.add_can_any(
+                    next,
+                    AdHocCanAny::new(|input| AnyType::Any.type_match(input)),
+                );
+                h.add_tran(next, AnyType::Any, inner);
+                next = h.add_tran_with_auto_next(next, '$');
+                h.set_accept_status(next, PartMath);
+
+                // 尖括号 html标签/注释/自动链接
+                next = h.add_tran_with_auto_next(0, '<');
+                next = h.add_tran(1, '<', next);
+                let angle_start = next;
+                // 标签内部 不可跨行，也不可再出现 '<'
+                let inner = h.add_tran_with_auto_next(angle_start, '/');
+                h.add_tran(angle_start, '?', inner);
+                h.add_can_any(
+                    angle_start,
+                    AdHocCanAny::new(|c| (AnyType::Ascii & AnyType::Alphabet).type_match(c)),
+                );
+                h.add_tran(angle_start, AnyType::Ascii & AnyType::Alphabet, inner);
+                h.add_can_any(
+                    inner,
+                    AdHocCanAny::new(|c| {
+                        (!(AnyType::Char('\n') | AnyType::Char('<'))).type_match(c)
+                    }),
+                );
+                h.add_tran(inner, !(AnyType::Char('\n') | AnyType::Char('<')), inner);
+                let angle_end = h.add_tran_with_auto_next(inner, '>');
+                h.set_accept_status(angle_end, PartAngle);
+                // <! 声明 或 注释
+                next = h.add_tran_with_auto_next(angle_start, '!');
+                let bang = next;
+                h.add_tran(bang, '>', angle_end);
+                h.add_can_any(
+                    bang,
+                    AdHocCanAny::new(|c| {
+                        (!(AnyType::Char('\n') | AnyType::Char('<'))).type_match(c)
+                    }),
+                );
+                h.add_tran(bang, !(AnyType::Char('\n') | AnyType::Char('<')), inner);
+                next = h.add_tran_with_auto_next(bang, '-');
+                h.add_tran(next, '>', angle_end);
+                h.add_can_any(
+                    next,
+                    AdHocCanAny::new(|c| {
+                        (!(AnyType::Char('\n') | AnyType::Char('<'))).type_match(c)
+                    }),
+                );
+                h.add_tran(next, !(AnyType::Char('\n') | AnyType::Char('<')), inner);
+                // 注释内部 可以跨行，直到 "-->"
+                next = h.add_tran_with_auto_next(next, '-');
+                let comment = next;
+                h.add_can_any(
+                    comment,
+                    AdHocCanAny::new(|input| AnyType::Any.type_match(input)),
+                );
+                h.add_tran(comment, AnyType::Any, comment);
+                next = h.add_tran_with_auto_next(comment, '-');
+                h.add_can_any(
+                    next,
+                    AdHocCanAny::new(|input| AnyType::Any.type_match(input)),
+                );
+                h.add_tran(next, AnyType::Any, comment);
+                next = h.add_tran_with_auto_next(next, '-');
+                let comment_end = next;
+                h.add_can_any(
+                    comment_end,
+                    AdHocCanAny::new(|input| AnyType::Any.type_match(input)),
+                );
+                h.add_tran(comment_end, AnyType::Any, comment);
+                h.add_tran(comment_end, '-', comment_end);
+                next = h.add_tran_with_auto_next(comment_end, '>');
+                h.set_accept_status(next, PartAngle);
+
+                // 字符引用
+                let alnum = AnyType::Ascii & (AnyType::Alphabet | AnyType::Digit);
+                next = h.add_tran_with_auto_next(0, '&');
+                next = h.add_tran(1, '&', next);
+                let amp = next;
+                let entity_end = h.counter_next();
+                // 命名引用 &name;
+                h.add_can_any(
+                    amp,
+                    AdHocCanAny::new(|c| {
+                        (AnyType::Ascii & (AnyType::Alphabet | AnyType::Digit)).type_match(c)
+                    }),
+                );
+                next = h.add_tran_with_auto_next(amp, alnum.clone());
+                h.add_can_any(
+                    next,
+                    AdHocCanAny::new(|c| {
+                        (AnyType::Ascii & (AnyType::Alphabet | AnyType::Digit)).type_match(c)
+                    }),
+                );
+                h.add_tran(next, alnum.clone(), next);
+                h.add_tran(next, ';', entity_end);
+                // 十进制 &#35;
+                let hash = h.add_tran_with_auto_next(amp, '#');
+                h.add_can_any(hash, AdHocCanAny::new(|c| AnyType::Digit.type_match(c)));
+                next = h.add_tran_with_auto_next(hash, AnyType::Digit);
+                h.add_can_any(next, AdHocCanAny::new(|c| AnyType::Digit.type_match(c)));
+                h.add_tran(next, AnyType::Digit, next);
+                h.add_tran(next, ';', entity_end);
+                // 十六进制 &#x4E2D; 位数与取值在生成 token 时检查
+                next = h.add_tran_with_auto_next(hash, 'x');
+                h.add_tran(hash, 'X', next);
+                let hex_start = next;
+                h.add_can_any(
+                    hex_start,
+                    AdHocCanAny::new(|c| {
+                        (AnyType::Ascii & (AnyType::Alphabet | AnyType::Digit)).type_match(c)
+                    }),
+                );
+                next = h.add_tran_with_auto_next(hex_start, alnum.clone());
+                h.add_can_any(
+                    next,
+                    AdHocCanAny::new(|c| {
+                        (AnyType::Ascii & (AnyType::Alphabet | AnyType::Digit)).type_match(c)
+                    }),
+                );
+                h.add_tran(next, alnum, next);
+                h.add_tran(next, ';', entity_end);
+                h.set_accept_status(entity_end, PartEntity);
             })
             .build()
     }
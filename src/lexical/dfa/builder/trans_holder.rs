@@ -47,6 +47,11 @@ impl TransHolder {
         self.add_tran(src, input, next)
     }
 
+    /// 分配一个新的状态，用于多个分支汇合到同一状态
+    pub fn counter_next(&mut self) -> usize {
+        self.counter.next().unwrap()
+    }
+
     pub fn add_can_any(&mut self, src: usize, any_tran: Box<dyn CanAny>) {
         self.any_trans.insert(src, any_tran);
     }
@@ -77,7 +77,9 @@ impl DFA {
             }else{
                 self.reset_status()
             };
-            NextStatus::Plain(self.buff.clone(), sta, input)
+            let res = NextStatus::Plain(self.buff.clone(), sta, input);
+            self.buff.clear();
+            res
         }
     }
 
@@ -100,6 +102,12 @@ impl DFA {
     pub fn reset(&mut self){
         self.buff.clear();
     }
+
+    /// 回到输入开始时的状态，用于复用自动机读取新的输入
+    pub fn restart(&mut self) {
+        self.buff.clear();
+        self.line_status = LineStatus::LineStart;
+    }
 }
 
 #[cfg(test)]
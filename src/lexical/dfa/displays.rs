@@ -36,23 +36,23 @@ impl Display for super::wraps::Status {
                 match align {
                     std::fmt::Alignment::Left => {
                         write!(f, "{}", s)?;
-                        for _ in 0..w - s.len() {
+                        for _ in 0..w.saturating_sub(s.len()) {
                             write!(f, "{}", c)?;
                         }
                     }
                     std::fmt::Alignment::Right => {
-                        for _ in 0..w - s.len() {
+                        for _ in 0..w.saturating_sub(s.len()) {
                             write!(f, "{}", c)?;
                         }
                         write!(f, "{}", s)?;
                     }
                     std::fmt::Alignment::Center => {
-                        let left = (w - s.len()) / 2;
+                        let left = w.saturating_sub(s.len()) / 2;
                         for _ in 0..left {
                             write!(f, "{}", c)?;
                         }
                         write!(f, "{}", s)?;
-                        for _ in 0..w - left - s.len() {
+                        for _ in 0..w.saturating_sub(left + s.len()) {
                             write!(f, "{}", c)?;
                         }
                     }
@@ -79,23 +79,23 @@ impl Display for super::wraps::InputChar {
                 match align {
                     std::fmt::Alignment::Left => {
                         write!(f, "{}", s)?;
-                        for _ in 0..w - s.len() {
+                        for _ in 0..w.saturating_sub(s.len()) {
                             write!(f, "{}", c)?;
                         }
                     }
                     std::fmt::Alignment::Right => {
-                        for _ in 0..w - s.len() {
+                        for _ in 0..w.saturating_sub(s.len()) {
                             write!(f, "{}", c)?;
                         }
                         write!(f, "{}", s)?;
                     }
                     std::fmt::Alignment::Center => {
-                        let left = (w - s.len()) / 2;
+                        let left = w.saturating_sub(s.len()) / 2;
                         for _ in 0..left {
                             write!(f, "{}", c)?;
                         }
                         write!(f, "{}", s)?;
-                        for _ in 0..w - left - s.len() {
+                        for _ in 0..w.saturating_sub(left + s.len()) {
                             write!(f, "{}", c)?;
                         }
                     }
@@ -1,4 +1,4 @@
-use std::str::Chars;
+use std::{cell::RefCell, str::Chars};
 
 use serde::de::IntoDeserializer;
 
@@ -8,7 +8,7 @@ use self::{
         core::DFA,
         wraps::{InputChar, Status},
     },
-    token_trait::FullToken,
+    token_trait::{FullToken, TokenKind},
     tokens::{Eof, Plain},
 };
 
@@ -19,7 +19,12 @@ pub mod tokens;
 
 type RawData = Vec<char>;
 
-pub struct Token(Box<dyn FullToken>, String);
+pub struct Token(pub Box<dyn FullToken>, pub String);
+
+thread_local! {
+    // 构建自动机的开销较大，逐行识别块结构时复用
+    static CACHED_DFA: RefCell<Option<DFA>> = const { RefCell::new(None) };
+}
 
 pub struct LexicalLoader<'s> {
     // 输入的字符
@@ -42,6 +47,23 @@ impl<'s> LexicalLoader<'s> {
             dfa,
         }
     }
+
+    /// 读取 `input` 开头的一个单元，复用当前线程缓存的自动机
+    pub fn first_token(input: &str) -> Option<Token> {
+        let dfa = CACHED_DFA
+            .with(|cache| cache.take())
+            .unwrap_or_else(DFABuilder::init);
+        let mut loader = LexicalLoader {
+            input: input.chars(),
+            status: dfa.init(),
+            to_dfa: None,
+            dfa,
+        };
+        let token = loader.next();
+        loader.dfa.restart();
+        CACHED_DFA.with(|cache| cache.replace(Some(loader.dfa)));
+        token
+    }
 }
 impl<'s> LexicalLoader<'s> {
     pub fn next_token(&mut self) -> Token {
@@ -126,9 +148,23 @@ impl<'s> LexicalLoader<'s> {
     }
 }
 
+impl<'s> Iterator for LexicalLoader<'s> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.next_token();
+        // 读取到 eof 后迭代结束
+        if token.0.kind() == TokenKind::Eof {
+            None
+        } else {
+            Some(token)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::lexical::token_trait::FromTokenMeta;
+    use crate::lexical::{token_trait::FromTokenMeta, tokens::titles::TitleToken};
 
     use super::*;
 
@@ -169,6 +205,28 @@ mod test {
         assert_eq!(r, "");
     }
 
+    #[test]
+    fn test_kind() {
+        let kinds = LexicalLoader::new("## *a*".chars())
+            .map(|Token(t, _)| t.kind())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Title,
+                TokenKind::SepChar,
+                TokenKind::Star,
+                TokenKind::Plain,
+                TokenKind::Star
+            ]
+        );
+
+        let Token(t, _) = LexicalLoader::new("### x".chars()).next_token();
+        assert!(t.is::<TitleToken>());
+        assert_eq!(t.downcast_ref::<TitleToken>().unwrap().level, 3);
+        assert!(t.downcast_ref::<Plain>().is_none());
+    }
+
     #[test]
     fn test_title_read() {
         let input="## Md解析器 ***全新版本*** 好耶 [abab](http://www.bilibili b站) emm\n\n好家伙，这么顶  \n\n";
@@ -404,11 +462,7 @@ mod test {
         //引用 二级
         let Token(_t, r) = lex.next_token();
         println!("token raw: {:?}", r);
-        assert_eq!(r, ">>");
-
-        let Token(_t, r) = lex.next_token();
-        println!("token raw: {:?}", r);
-        assert_eq!(r, " ");
+        assert_eq!(r, ">> ");
 
         let Token(_t, r) = lex.next_token();
         println!("token raw: {:?}", r);
@@ -418,18 +472,34 @@ mod test {
         println!("token raw: {:?}", r);
         assert_eq!(r, "\n");
 
-        // 缩进段落
+        // 二级缩进
         let Token(_t, r) = lex.next_token();
         println!("token raw: {:?}", r);
-        assert_eq!(r, "    ");
+        assert_eq!(r, "        ");
 
-        // 缩进段落
-        let Token(_t, r) = lex.next_token();
-        println!("token raw: {:?}", r);
-        assert_eq!(r, "    ");
-        //二级缩进
         let Token(_t, r) = lex.next_token();
         println!("token raw: {:?}", r);
         assert_eq!(r, "codeblock");
     }
+
+    #[test]
+    fn test_first_token() {
+        // 复用的自动机不受上一次输入的影响
+        for input in [
+            "#a",
+            "## title",
+            "- item",
+            "12) x",
+            "***",
+            "plain text",
+            "",
+            "## b",
+        ] {
+            let fresh = LexicalLoader::new(input.chars())
+                .next()
+                .map(|Token(t, r)| (t.kind(), r));
+            let cached = LexicalLoader::first_token(input).map(|Token(t, r)| (t.kind(), r));
+            assert_eq!(cached, fresh, "{:?}", input);
+        }
+    }
 }
@@ -0,0 +1,137 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::{FromToken, FullToken, TokenMeta};
+
+/// token 的名称与全部数据，可以序列化保存，
+/// 反序列化后通过 [`TokenFactory`] 重建具体的 token
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TokenData {
+    pub name: String,
+    #[serde(default)]
+    pub data: BTreeMap<String, TokenMeta>,
+}
+
+impl TokenData {
+    pub fn new(token: &dyn FullToken) -> Self {
+        Self {
+            name: token.name().to_string(),
+            data: token
+                .all_data()
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        }
+    }
+
+    /// 重建为类型已知的 token，名称不同或数据不完整时返回 `None`
+    pub fn rebuild<T: FromToken>(&self) -> Option<T> {
+        if self.name != T::token_name() {
+            return None;
+        }
+        T::from_token(Box::new(DataToken {
+            name: T::token_name(),
+            data: self.data.clone(),
+        }))
+    }
+}
+
+// 通过 `FromToken` 构造具体 token 的函数
+type Builder = fn(Box<dyn FullToken>) -> Option<Box<dyn FullToken>>;
+
+/// 按名称重建 token，每种 token 需要先注册
+#[derive(Default)]
+pub struct TokenFactory {
+    builders: HashMap<&'static str, Builder>,
+}
+
+impl TokenFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册 token，名称为 `T::token_name()`
+    pub fn register<T: FromToken + 'static>(mut self) -> Self {
+        self.builders.insert(T::token_name(), |src| {
+            T::from_token(src).map(|t| Box::new(t) as Box<dyn FullToken>)
+        });
+        self
+    }
+
+    /// 重建 token，名称未注册或数据不完整时返回 `None`
+    pub fn build(&self, data: &TokenData) -> Option<Box<dyn FullToken>> {
+        let (name, builder) = self.builders.get_key_value(data.name.as_str())?;
+        builder(Box::new(DataToken {
+            name,
+            data: data.data.clone(),
+        }))
+    }
+}
+
+/// 只用于 `FromToken::from_token` 读取数据
+struct DataToken {
+    name: &'static str,
+    data: BTreeMap<String, TokenMeta>,
+}
+
+impl FullToken for DataToken {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn get_data(&self, name: &str) -> Option<TokenMeta> {
+        self.data.get(name).cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lexical::{token_trait::Number, tokens, LexicalLoader, Token};
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let factory = tokens::factory();
+        let input = "## 标题 [[Page#a|b]] `code` 12. $x$ &amp; <https://a.b> \\* x  \n    y";
+        for Token(token, raw) in LexicalLoader::new(input.chars()) {
+            let data = TokenData::new(&*token);
+            let json = serde_json::to_string(&data).unwrap();
+            let back = serde_json::from_str::<TokenData>(&json).unwrap();
+            assert_eq!(back, data, "{}", raw);
+
+            let rebuilt = factory.build(&back).unwrap();
+            assert_eq!(rebuilt.name(), token.name());
+            assert_eq!(rebuilt.all_data(), token.all_data());
+        }
+        assert!(factory
+            .build(&TokenData {
+                name: String::from("unknown"),
+                data: BTreeMap::new(),
+            })
+            .is_none());
+    }
+
+    #[test]
+    fn test_number_variant() {
+        for meta in [
+            TokenMeta::Num(Number::P(1)),
+            TokenMeta::Num(Number::N(1)),
+            TokenMeta::Num(Number::FShort(0.1)),
+            TokenMeta::Num(Number::F(0.1)),
+            TokenMeta::Char('a'),
+            TokenMeta::Str(String::from("a")),
+            TokenMeta::Vec(vec![TokenMeta::Bool(true)]),
+        ] {
+            let json = serde_json::to_string(&meta).unwrap();
+            assert_eq!(serde_json::from_str::<TokenMeta>(&json).unwrap(), meta);
+        }
+        assert_eq!(
+            serde_json::to_string(&TokenMeta::Num(Number::N(1))).unwrap(),
+            r#"{"num":{"n":1}}"#
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use super::{FromTokenMeta, IntoTokenMeta, Number, TokenMeta};
 
 impl IntoTokenMeta for u8 {
@@ -224,3 +226,255 @@ where
         }
     }
 }
+
+impl IntoTokenMeta for usize {
+    fn into_token_meta(self) -> TokenMeta {
+        TokenMeta::Num(Number::P(self as u64))
+    }
+}
+
+impl FromTokenMeta for usize {
+    fn from_token_meta(src: &TokenMeta) -> Option<Self> {
+        if let TokenMeta::Num(Number::P(u)) = src {
+            (*u).try_into().ok()
+        } else {
+            None
+        }
+    }
+}
+
+impl IntoTokenMeta for isize {
+    fn into_token_meta(self) -> TokenMeta {
+        TokenMeta::Num(Number::N(self as i64))
+    }
+}
+
+impl FromTokenMeta for isize {
+    fn from_token_meta(src: &TokenMeta) -> Option<Self> {
+        if let TokenMeta::Num(Number::N(i)) = src {
+            (*i).try_into().ok()
+        } else {
+            None
+        }
+    }
+}
+
+impl IntoTokenMeta for &str {
+    fn into_token_meta(self) -> TokenMeta {
+        TokenMeta::Str(self.to_string())
+    }
+}
+
+impl<T> IntoTokenMeta for Option<T>
+where
+    T: IntoTokenMeta,
+{
+    fn into_token_meta(self) -> TokenMeta {
+        match self {
+            Some(v) => v.into_token_meta(),
+            None => TokenMeta::Null,
+        }
+    }
+}
+
+impl<T> FromTokenMeta for Option<T>
+where
+    T: FromTokenMeta,
+{
+    fn from_token_meta(src: &TokenMeta) -> Option<Self> {
+        if let TokenMeta::Null = src {
+            Some(None)
+        } else {
+            T::from_token_meta(src).map(Some)
+        }
+    }
+}
+
+impl<T> IntoTokenMeta for Box<T>
+where
+    T: IntoTokenMeta,
+{
+    fn into_token_meta(self) -> TokenMeta {
+        (*self).into_token_meta()
+    }
+}
+
+impl<T> FromTokenMeta for Box<T>
+where
+    T: FromTokenMeta,
+{
+    fn from_token_meta(src: &TokenMeta) -> Option<Self> {
+        T::from_token_meta(src).map(Box::new)
+    }
+}
+
+impl<T> IntoTokenMeta for BTreeMap<String, T>
+where
+    T: IntoTokenMeta,
+{
+    fn into_token_meta(self) -> TokenMeta {
+        TokenMeta::Map(
+            self.into_iter()
+                .map(|(k, v)| (k, v.into_token_meta()))
+                .collect(),
+        )
+    }
+}
+
+impl<T> FromTokenMeta for BTreeMap<String, T>
+where
+    T: FromTokenMeta,
+{
+    fn from_token_meta(src: &TokenMeta) -> Option<Self> {
+        if let TokenMeta::Map(m) = src {
+            m.iter()
+                .map(|(k, v)| Some((k.clone(), T::from_token_meta(v)?)))
+                .collect()
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> IntoTokenMeta for HashMap<String, T>
+where
+    T: IntoTokenMeta,
+{
+    fn into_token_meta(self) -> TokenMeta {
+        TokenMeta::Map(
+            self.into_iter()
+                .map(|(k, v)| (k, v.into_token_meta()))
+                .collect(),
+        )
+    }
+}
+
+impl<T> FromTokenMeta for HashMap<String, T>
+where
+    T: FromTokenMeta,
+{
+    fn from_token_meta(src: &TokenMeta) -> Option<Self> {
+        if let TokenMeta::Map(m) = src {
+            m.iter()
+                .map(|(k, v)| Some((k.clone(), T::from_token_meta(v)?)))
+                .collect()
+        } else {
+            None
+        }
+    }
+}
+
+// 元组保存为等长的序列
+macro_rules! tuple_token_meta {
+    ($($t:ident : $idx:tt),+) => {
+        impl<$($t),+> IntoTokenMeta for ($($t,)+)
+        where
+            $($t: IntoTokenMeta),+
+        {
+            fn into_token_meta(self) -> TokenMeta {
+                TokenMeta::Vec(vec![$(self.$idx.into_token_meta()),+])
+            }
+        }
+
+        impl<$($t),+> FromTokenMeta for ($($t,)+)
+        where
+            $($t: FromTokenMeta),+
+        {
+            fn from_token_meta(src: &TokenMeta) -> Option<Self> {
+                match src {
+                    TokenMeta::Vec(v) if v.len() == [$($idx),+].len() => {
+                        Some(($($t::from_token_meta(&v[$idx])?,)+))
+                    }
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+tuple_token_meta!(A: 0, B: 1);
+tuple_token_meta!(A: 0, B: 1, C: 2);
+tuple_token_meta!(A: 0, B: 1, C: 2, D: 3);
+
+#[cfg(test)]
+mod test {
+    use full_token_derive_macro::FullToken;
+
+    use crate::lexical::token_trait::{FromToken, FullToken, TokenData};
+
+    use super::*;
+
+    #[derive(FullToken, Clone, Debug, PartialEq)]
+    #[token(name = "inner_mock")]
+    struct Inner {
+        level: u8,
+    }
+
+    #[derive(FullToken, Debug, PartialEq)]
+    #[token(name = "outer_mock")]
+    struct Outer {
+        title: Option<String>,
+        missing: Option<String>,
+        attrs: HashMap<String, String>,
+        sorted: BTreeMap<String, usize>,
+        pair: (isize, char),
+        boxed: Box<u8>,
+        inner: Inner,
+    }
+
+    fn outer() -> Outer {
+        Outer {
+            title: Some(String::from("t")),
+            missing: None,
+            attrs: HashMap::from([(String::from("k"), String::from("v"))]),
+            sorted: BTreeMap::from([(String::from("a"), 1)]),
+            pair: (-1, 'c'),
+            boxed: Box::new(2),
+            inner: Inner { level: 3 },
+        }
+    }
+
+    #[test]
+    fn test_nested() {
+        let token = outer();
+        assert_eq!(token.get_data("missing"), Some(TokenMeta::Null));
+        assert_eq!(
+            token.get_data("pair"),
+            Some(TokenMeta::Vec(vec![
+                TokenMeta::Num(Number::N(-1)),
+                TokenMeta::Char('c')
+            ]))
+        );
+        assert_eq!(
+            token.get_data("inner"),
+            Some(TokenMeta::Token(TokenData {
+                name: String::from("inner_mock"),
+                data: BTreeMap::from([(String::from("level"), TokenMeta::Num(Number::P(3)))]),
+            }))
+        );
+        assert_eq!(Outer::from_token(Box::new(outer())), Some(outer()));
+
+        let data = TokenData::new(&token);
+        let json = serde_json::to_string(&data).unwrap();
+        let back = serde_json::from_str::<TokenData>(&json).unwrap();
+        assert_eq!(back.rebuild::<Outer>(), Some(outer()));
+        assert_eq!(back.rebuild::<Inner>(), None);
+    }
+
+    #[test]
+    fn test_mismatch() {
+        assert_eq!(
+            <(u8, u8)>::from_token_meta(&vec![1u8].into_token_meta()),
+            None
+        );
+        assert_eq!(usize::from_token_meta(&(-1isize).into_token_meta()), None);
+        assert_eq!("s".into_token_meta(), TokenMeta::Str(String::from("s")));
+        assert_eq!(
+            BTreeMap::<String, u8>::from_token_meta(&TokenMeta::Map(BTreeMap::from([(
+                String::from("x"),
+                TokenMeta::Bool(true)
+            )]))),
+            None
+        );
+    }
+}
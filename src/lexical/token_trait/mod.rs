@@ -1,7 +1,12 @@
+use std::{any::Any, collections::BTreeMap};
+
 use serde::ser::SerializeStruct;
 
+mod factory;
 mod impl_token_meta;
 
+pub use factory::{TokenData, TokenFactory};
+
 pub trait TokenTrait {
     // token 名称
     fn name(&self) -> &'static str;
@@ -12,6 +17,12 @@ pub trait TokenTrait {
 pub trait FullToken {
     // 完整token名称
     fn name(&self) -> &'static str;
+    // token 类型，自定义的 token 为 `TokenKind::Other`
+    fn kind(&self) -> TokenKind {
+        TokenKind::Other
+    }
+    // 用于向下转型为具体的 token
+    fn as_any(&self) -> &dyn Any;
     // 通过字段名称获取token内数据
     fn get_data(&self, _name: &str) -> Option<TokenMeta> {
         None
@@ -29,12 +40,56 @@ pub trait FullToken {
     }
 }
 
+impl dyn FullToken {
+    /// 是否为类型 `T` 的 token
+    pub fn is<T: FullToken + 'static>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// 借用为具体的 token，不复制数据
+    pub fn downcast_ref<T: FullToken + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+}
+
+/// 词法分析器产生的 token 类型，与 `lexical::tokens` 中的类型一一对应
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    SepChar,
+    ChangeLine,
+    NewParam,
+    HardBreak,
+    LinkStart,
+    ImgStart,
+    BoxMid,
+    BoxEnd,
+    SepLine,
+    Star,
+    Eof,
+    Trans,
+    Plain,
+    Angle,
+    CodeSnippet,
+    Entity,
+    Idented,
+    UnorderList,
+    OrderList,
+    Math,
+    Reference,
+    Title,
+    Wiki,
+    /// 不属于词法分析器的 token
+    Other,
+}
+
 pub trait FromToken: Sized+FullToken {
     fn token_name() -> &'static str;
     fn from_token(src: Box<dyn FullToken>) -> Option<Self>;
 }
 
-#[derive(Clone, Copy, Debug)]
+/// 数值，序列化时保留变体，反序列化后与原值相同
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Number {
     P(u64),
     N(i64),
@@ -42,13 +97,22 @@ pub enum Number {
     F(f64),
 }
 
-#[derive(Clone, Debug)]
+/// token 内的数据，序列化为以变体名为键的对象，如 `{"str": "abc"}`
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TokenMeta  {
+    /// `Option` 的 `None`
+    Null,
     Num(Number),
     Bool(bool),
     Char(char),
     Str(String),
+    /// 序列与元组
     Vec(Vec<TokenMeta>),
+    /// 以字符串为键的映射
+    Map(BTreeMap<String, TokenMeta>),
+    /// 嵌套的 token
+    Token(TokenData),
 }
 
 pub trait IntoTokenMeta {
@@ -59,55 +123,6 @@ pub trait FromTokenMeta: Sized {
     fn from_token_meta(src: &TokenMeta) -> Option<Self>;
 }
 
-impl serde::Serialize for Number {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        match self {
-            Number::P(n) => {
-                if let Ok(u) = TryInto::<u8>::try_into(*n) {
-                    serializer.serialize_u8(u)
-                } else if let Ok(u) = TryInto::<u16>::try_into(*n) {
-                    serializer.serialize_u16(u)
-                } else if let Ok(v) = TryInto::<u32>::try_into(*n) {
-                    serializer.serialize_u32(v)
-                } else {
-                    serializer.serialize_u64(*n)
-                }
-            }
-            Number::N(i) => {
-                if let Ok(u) = TryInto::<i8>::try_into(*i) {
-                    serializer.serialize_i8(u)
-                } else if let Ok(u) = TryInto::<i16>::try_into(*i) {
-                    serializer.serialize_i16(u)
-                } else if let Ok(v) = TryInto::<i32>::try_into(*i) {
-                    serializer.serialize_i32(v)
-                } else {
-                    serializer.serialize_i64(*i)
-                }
-            }
-            Number::FShort(f) => serializer.serialize_f32(*f),
-            Number::F(f) => serializer.serialize_f64(*f),
-        }
-    }
-}
-
-impl serde::Serialize for TokenMeta {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        match self {
-            TokenMeta::Num(n) => n.serialize(serializer),
-            TokenMeta::Bool(b) => b.serialize(serializer),
-            TokenMeta::Char(c) => c.serialize(serializer),
-            TokenMeta::Str(s) => s.serialize(serializer),
-            TokenMeta::Vec(v) => v.serialize(serializer),
-        }
-    }
-}
-
 impl serde::Serialize for dyn FullToken {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
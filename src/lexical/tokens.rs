@@ -17,6 +17,10 @@ macro_rules! token_generator {
 
         impl FullToken for $ty{
             fn name(&self) -> &'static str { $name }
+            fn kind(&self) -> $crate::lexical::token_trait::TokenKind {
+                $crate::lexical::token_trait::TokenKind::$ty
+            }
+            fn as_any(&self) -> &dyn std::any::Any { self }
         }
 
         impl TokenTrait for $ty{
@@ -36,37 +40,74 @@ macro_rules! token_generator {
     }
 }
 
+pub mod angle;
 pub mod code_snippet;
+pub mod entities;
+pub mod entity;
+pub mod idented;
+pub mod list;
+pub mod math;
 pub mod reference;
 pub mod titles;
+pub mod wiki;
 
 use crate::lexical::token_trait::FromToken;
+use crate::lexical::token_trait::TokenFactory;
 use crate::lexical::token_trait::TokenTrait;
 use crate::lexical::FullToken;
 use full_token_derive_macro::FullToken;
+pub use idented::PartIdented as Idented;
+pub use list::{PartOrderList as OrderList, PartUnorderList as UnorderList};
 pub use reference::PartRef as Reference;
 
 token_generator![
     "sep_char": SepChar,       // ` `
     "change_line": ChangeLine, // \n
-    "new_param": NewParam,     // `  \n`
-    "idented": Idented,        // `    ` | \t
+    "new_param": NewParam,     // 空行 \n
+    "hard_break": HardBreak,   // `  \n` | `\` \n
     // orthers
     "link_start": LinkStart,     // [
     "img_start": ImgStart,       // ![
     "box_mid": BoxMid,           // ](
     "box_end": BoxEnd,           // )
-    "unorder_list": UnorderList, // -
-    "order_list": OrderList,     // 0|1|2|3|4|5|6|7|8|9 .
     "SeperLine": SepLine,        // ---
     "star": Star,                // *
     // 特殊token
     "eof": Eof
 ];
 
+/// 可以重建词法分析器产生的全部 token 的工厂
+pub fn factory() -> TokenFactory {
+    TokenFactory::new()
+        .register::<SepChar>()
+        .register::<ChangeLine>()
+        .register::<NewParam>()
+        .register::<HardBreak>()
+        .register::<LinkStart>()
+        .register::<ImgStart>()
+        .register::<BoxMid>()
+        .register::<BoxEnd>()
+        .register::<SepLine>()
+        .register::<Star>()
+        .register::<Eof>()
+        .register::<FullTrans>()
+        .register::<Plain>()
+        .register::<angle::Angle>()
+        .register::<code_snippet::CodeSnippet>()
+        .register::<entity::Entity>()
+        .register::<idented::Idented>()
+        .register::<list::UnorderList>()
+        .register::<list::OrderList>()
+        .register::<math::Math>()
+        .register::<reference::Reference>()
+        .register::<titles::TitleToken>()
+        .register::<wiki::Wiki>()
+}
+
 #[derive(FullToken)]
+#[token(name = "Trans", kind = "Trans")]
 pub struct FullTrans {
-    ch: char,
+    pub ch: char,
 }
 pub struct Trans;
 
@@ -81,8 +122,9 @@ impl TokenTrait for Trans {
 }
 
 #[derive(FullToken)]
+#[token(name = "plain", kind = "Plain")]
 pub struct Plain {
-    pub(super) inner: String,
+    pub inner: String,
 }
 
 impl Plain {
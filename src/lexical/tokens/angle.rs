@@ -0,0 +1,56 @@
+use full_token_derive_macro::FullToken;
+
+use super::super::token_trait::{FullToken, TokenTrait};
+
+/// 尖括号包围的部分 `<...>`
+///
+/// html 标签、注释与自动链接在词法阶段都是这一形式，
+/// 具体属于哪一种由语法分析判断
+pub struct PartAngle;
+
+impl TokenTrait for PartAngle {
+    fn name(&self) -> &'static str {
+        "angle"
+    }
+
+    fn to_full(&self, buff: &[char]) -> Box<dyn FullToken> {
+        // 去除首尾的 '<' '>'
+        let inner = String::from_iter(buff[1..buff.len() - 1].iter());
+        Box::new(Angle { inner })
+    }
+}
+
+#[derive(FullToken)]
+#[token(name = "angle", kind = "Angle")]
+pub struct Angle {
+    pub inner: String,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lexical::token_trait::FromTokenMeta;
+
+    use super::*;
+
+    #[test]
+    fn test_inner() {
+        let buff = "<a href=\"#\">".chars().collect::<Vec<_>>();
+        let res = PartAngle.to_full(&buff);
+
+        assert_eq!(
+            "a href=\"#\"",
+            String::from_token_meta(&res.get_data("inner").unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_comment() {
+        let buff = "<!-- a > b -->".chars().collect::<Vec<_>>();
+        let res = PartAngle.to_full(&buff);
+
+        assert_eq!(
+            "!-- a > b --",
+            String::from_token_meta(&res.get_data("inner").unwrap()).unwrap()
+        );
+    }
+}
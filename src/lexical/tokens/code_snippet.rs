@@ -34,7 +34,7 @@ impl TokenTrait for PartCodeSnippet {
     }
 }
 #[derive(FullToken)]
-#[token(name = "code_snippet")]
+#[token(name = "code_snippet", kind = "CodeSnippet")]
 pub struct CodeSnippet {
     pub inner: String,
 }
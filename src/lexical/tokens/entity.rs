@@ -0,0 +1,108 @@
+use full_token_derive_macro::FullToken;
+
+use super::{
+    super::token_trait::{FullToken, TokenTrait},
+    entities::ENTITIES,
+};
+
+/// 字符引用 `&name;` `&#35;` `&#x4E2D;`
+pub struct PartEntity;
+
+impl TokenTrait for PartEntity {
+    fn name(&self) -> &'static str {
+        "entity"
+    }
+
+    fn to_full(&self, buff: &[char]) -> Box<dyn FullToken> {
+        // 去除首尾的 '&' ';'
+        let inner = String::from_iter(buff[1..buff.len() - 1].iter());
+        let value = decode(&inner).unwrap_or_default();
+        Box::new(Entity { inner, value })
+    }
+}
+
+/// `value` 为空表示不是合法的字符引用，应当作为普通文本
+#[derive(FullToken)]
+#[token(name = "entity", kind = "Entity")]
+pub struct Entity {
+    pub inner: String,
+    pub value: String,
+}
+
+/// 解码 `&` 与 `;` 之间的部分
+pub fn decode(inner: &str) -> Option<String> {
+    if let Some(num) = inner.strip_prefix('#') {
+        let (radix, digits, max_len) = match num.strip_prefix(['x', 'X']) {
+            Some(hex) => (16, hex, 6),
+            None => (10, num, 7),
+        };
+        if digits.is_empty() || digits.len() > max_len || !digits.chars().all(|c| c.is_digit(radix))
+        {
+            return None;
+        }
+        // 非法码点替换为 U+FFFD
+        let c = u32::from_str_radix(digits, radix)
+            .ok()
+            .filter(|c| *c != 0)
+            .and_then(char::from_u32)
+            .unwrap_or('\u{FFFD}');
+        Some(c.to_string())
+    } else {
+        ENTITIES
+            .binary_search_by(|(name, _)| (*name).cmp(inner))
+            .ok()
+            .map(|idx| ENTITIES[idx].1.to_string())
+    }
+}
+
+/// 解码文本中全部的字符引用，用于链接目标与标题
+pub fn decode_all(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut rest = src;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 32)
+            .and_then(|end| decode(&rest[1..end + 1]).map(|v| (v, end + 2)));
+        match decoded {
+            Some((value, len)) => {
+                out.push_str(&value);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("amp").as_deref(), Some("&"));
+        assert_eq!(decode("copy").as_deref(), Some("\u{A9}"));
+        assert_eq!(decode("ngE").as_deref(), Some("\u{2267}\u{338}"));
+        assert_eq!(decode("#35").as_deref(), Some("#"));
+        assert_eq!(decode("#x4E2D").as_deref(), Some("中"));
+        assert_eq!(decode("#X22").as_deref(), Some("\""));
+        assert_eq!(decode("#0").as_deref(), Some("\u{FFFD}"));
+        assert_eq!(decode("#xD800").as_deref(), Some("\u{FFFD}"));
+        assert_eq!(decode("#x110000").as_deref(), Some("\u{FFFD}"));
+        assert_eq!(decode("#12345678"), None);
+        assert_eq!(decode("#"), None);
+        assert_eq!(decode("nosuch"), None);
+    }
+
+    #[test]
+    fn test_decode_all() {
+        assert_eq!(decode_all("a&amp;b &nosuch; &#x41;&"), "a&b &nosuch; A&");
+    }
+}
@@ -0,0 +1,59 @@
+use full_token_derive_macro::FullToken;
+
+use super::super::token_trait::{FullToken, TokenTrait};
+
+/// 行首缩进，至少 4 列，制表符对齐到 4 的倍数列
+pub struct PartIdented;
+
+impl TokenTrait for PartIdented {
+    fn name(&self) -> &'static str {
+        "idented"
+    }
+
+    fn to_full(&self, buff: &[char]) -> Box<dyn FullToken> {
+        Box::new(Idented {
+            width: columns(0, buff.iter().copied()) as u64,
+        })
+    }
+}
+
+#[derive(FullToken)]
+#[token(name = "idented", kind = "Idented")]
+pub struct Idented {
+    pub width: u64,
+}
+
+/// 从第 `start` 列开始的空白结束时所在的列
+pub fn columns<I: IntoIterator<Item = char>>(start: usize, whitespace: I) -> usize {
+    whitespace.into_iter().fold(start, |col, c| match c {
+        '\t' => col + 4 - col % 4,
+        _ => col + 1,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lexical::{token_trait::FromTokenMeta, LexicalLoader, Token};
+
+    #[test]
+    fn test_width() {
+        for (src, raw, width) in [
+            ("    a", "    ", 4),
+            ("\ta", "\t", 4),
+            ("  \ta", "  \t", 4),
+            ("   \t\ta", "   \t\t", 8),
+            ("      a", "      ", 6),
+            (" \t  a", " \t  ", 6),
+        ] {
+            let Token(t, r) = LexicalLoader::new(src.chars()).next_token();
+            assert_eq!(r, raw);
+            assert_eq!(
+                u64::from_token_meta(&t.get_data("width").unwrap()),
+                Some(width)
+            );
+        }
+        // 不足 4 列不是缩进
+        let Token(t, _) = LexicalLoader::new("   a".chars()).next_token();
+        assert_ne!(t.name(), "idented");
+    }
+}
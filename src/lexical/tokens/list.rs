@@ -0,0 +1,89 @@
+use full_token_derive_macro::FullToken;
+
+use super::super::token_trait::{FullToken, TokenTrait};
+
+/// 无序列表标记 `-` `*` `+`
+pub struct PartUnorderList;
+
+impl TokenTrait for PartUnorderList {
+    fn name(&self) -> &'static str {
+        "unorder_list"
+    }
+
+    fn to_full(&self, buff: &[char]) -> Box<dyn FullToken> {
+        Box::new(UnorderList { marker: buff[0] })
+    }
+}
+
+#[derive(FullToken)]
+#[token(name = "unorder_list", kind = "UnorderList")]
+pub struct UnorderList {
+    pub marker: char,
+}
+
+/// 有序列表标记 `1.` `1)`，序号最多 9 位
+pub struct PartOrderList;
+
+impl TokenTrait for PartOrderList {
+    fn name(&self) -> &'static str {
+        "order_list"
+    }
+
+    fn to_full(&self, buff: &[char]) -> Box<dyn FullToken> {
+        let (delimiter, digits) = buff.split_last().unwrap();
+        let number = String::from_iter(digits).parse().unwrap_or_default();
+        Box::new(OrderList {
+            number,
+            delimiter: *delimiter,
+        })
+    }
+}
+
+#[derive(FullToken)]
+#[token(name = "order_list", kind = "OrderList")]
+pub struct OrderList {
+    pub number: u64,
+    pub delimiter: char,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lexical::{token_trait::FromTokenMeta, LexicalLoader, Token};
+
+    #[test]
+    fn test_order_list() {
+        for (src, raw, number, delimiter) in [
+            ("1. a", "1.", 1, '.'),
+            ("0012) a", "0012)", 12, ')'),
+            ("123456789. a", "123456789.", 123456789, '.'),
+        ] {
+            let Token(t, r) = LexicalLoader::new(src.chars()).next_token();
+            assert_eq!(r, raw);
+            assert_eq!(t.name(), "order_list");
+            assert_eq!(
+                u64::from_token_meta(&t.get_data("number").unwrap()),
+                Some(number)
+            );
+            assert_eq!(
+                char::from_token_meta(&t.get_data("delimiter").unwrap()),
+                Some(delimiter)
+            );
+        }
+        // 超过 9 位不是列表
+        let Token(t, _) = LexicalLoader::new("1234567890. a".chars()).next_token();
+        assert_ne!(t.name(), "order_list");
+    }
+
+    #[test]
+    fn test_unorder_list() {
+        for marker in ['-', '*', '+'] {
+            let src = format!("{} a", marker);
+            let Token(t, r) = LexicalLoader::new(src.chars()).next_token();
+            assert_eq!(r, marker.to_string());
+            assert_eq!(
+                char::from_token_meta(&t.get_data("marker").unwrap()),
+                Some(marker)
+            );
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use full_token_derive_macro::FullToken;
+
+use super::super::token_trait::{FullToken, TokenTrait};
+
+/// 数学公式 行内 `$…$` 与独立 `$$…$$`，内容原样保留
+pub struct PartMath;
+
+impl TokenTrait for PartMath {
+    fn name(&self) -> &'static str {
+        "math"
+    }
+
+    fn to_full(&self, buff: &[char]) -> Box<dyn FullToken> {
+        let display = buff.len() >= 4 && buff[..2] == ['$', '$'];
+        let fence = if display { 2 } else { 1 };
+        let inner = String::from_iter(buff[fence..buff.len() - fence].iter());
+        Box::new(Math { inner, display })
+    }
+}
+
+#[derive(FullToken)]
+#[token(name = "math", kind = "Math")]
+pub struct Math {
+    pub inner: String,
+    pub display: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lexical::{token_trait::FromTokenMeta, LexicalLoader, Token};
+
+    fn first(src: &str) -> (String, Option<(String, bool)>) {
+        let Token(t, raw) = LexicalLoader::new(src.chars()).next_token();
+        let data = (t.name() == "math").then(|| {
+            (
+                String::from_token_meta(&t.get_data("inner").unwrap()).unwrap(),
+                bool::from_token_meta(&t.get_data("display").unwrap()).unwrap(),
+            )
+        });
+        (raw, data)
+    }
+
+    #[test]
+    fn test_inline() {
+        assert_eq!(
+            first("$a_1 \\$ b$ c"),
+            (
+                String::from("$a_1 \\$"),
+                Some((String::from("a_1 \\"), false))
+            )
+        );
+        // 结束的 `$` 之前不能是空白
+        assert_eq!(
+            first("$a $ b$"),
+            (
+                String::from("$a $ b$"),
+                Some((String::from("a $ b"), false))
+            )
+        );
+        // 结束的 `$` 之前为空白或之后为数字时不能结束公式
+        assert_eq!(first("$x $ $x$5").1, None);
+        assert_eq!(
+            first("$x$5 y$ z"),
+            (
+                String::from("$x$5 y$"),
+                Some((String::from("x$5 y"), false))
+            )
+        );
+        // `$` 之后为数字或空白时不是公式
+        assert_eq!(first("$5 and $6").1, None);
+        assert_eq!(first("$ a$").1, None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            first("$$\nx^2 $ y\n$$"),
+            (
+                String::from("$$\nx^2 $ y\n$$"),
+                Some((String::from("\nx^2 $ y\n"), true))
+            )
+        );
+    }
+}
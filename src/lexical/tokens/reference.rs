@@ -11,13 +11,29 @@ impl TokenTrait for PartRef {
     }
 
     fn to_full(&self, buff: &[char]) -> Box<dyn FullToken> {
-        Box::new(Reference{depath:buff.len() as u64})
+        // 跳过 `>` 之间的空格
+        let depath = buff.iter().filter(|c| **c == '>').count();
+        Box::new(Reference{depath:depath as u64})
     }
 }
 
 #[derive(FullToken)]
-#[token(name="refer")]
+#[token(name="refer", kind="Reference")]
 pub struct Reference{
     pub depath:u64
 }
 
+
+#[cfg(test)]
+mod test {
+    use crate::lexical::{token_trait::FromTokenMeta, LexicalLoader, Token};
+
+    #[test]
+    fn test_depath() {
+        for (src, raw, depath) in [(">> a", ">> ", 2), ("> > >a", "> > >", 3), (">", ">", 1)] {
+            let Token(t, r) = LexicalLoader::new(src.chars()).next_token();
+            assert_eq!(r, raw);
+            assert_eq!(u64::from_token_meta(&t.get_data("depath").unwrap()), Some(depath));
+        }
+    }
+}
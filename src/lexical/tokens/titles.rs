@@ -19,7 +19,7 @@ macro_rules! title_token_gen {
 }
 
 #[derive(FullToken)]
-#[token(name = "title")]
+#[token(name = "title", kind = "Title")]
 pub struct TitleToken {
     pub level: u8,
 }
@@ -36,7 +36,7 @@ mod test {
 
     use full_token_derive_macro::FullToken;
 
-    use crate::lexical::token_trait::{FromToken, FullToken};
+    use crate::lexical::token_trait::{FromToken, FullToken, TokenData, TokenMeta};
 
     #[derive(FullToken, PartialEq, Eq, Debug)]
     #[token(name = "mcok")]
@@ -73,4 +73,110 @@ mod test {
         assert!(from_box.is_some());
         println!("{:?}", from_box);
     }
+
+    #[derive(FullToken, PartialEq, Debug)]
+    struct UnitToken;
+
+    #[derive(FullToken, PartialEq, Debug)]
+    struct TupleToken(u8, #[token(name = "text")] String);
+
+    #[derive(FullToken, PartialEq, Debug)]
+    enum EnumToken {
+        Empty,
+        Level(u8),
+        Named { text: String },
+    }
+
+    // 以字符串保存数值
+    mod as_str {
+        use crate::lexical::token_trait::TokenMeta;
+
+        pub fn into_token_meta(v: &u32) -> TokenMeta {
+            TokenMeta::Str(v.to_string())
+        }
+
+        pub fn from_token_meta(src: &TokenMeta) -> Option<u32> {
+            match src {
+                TokenMeta::Str(s) => s.parse().ok(),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(FullToken, PartialEq, Debug)]
+    struct OptionToken {
+        #[token(skip)]
+        cache: Vec<char>,
+        #[token(default)]
+        level: u8,
+        #[token(with = "as_str")]
+        count: u32,
+    }
+
+    fn round_trip<T: FromToken>(token: &T) -> Option<T> {
+        let json = serde_json::to_string(&TokenData::new(token)).unwrap();
+        serde_json::from_str::<TokenData>(&json).unwrap().rebuild()
+    }
+
+    #[test]
+    fn test_derive_shapes() {
+        assert!(UnitToken.get_all_name().is_empty());
+        assert_eq!(round_trip(&UnitToken), Some(UnitToken));
+
+        let tuple = TupleToken(1, String::from("a"));
+        assert_eq!(tuple.get_all_name(), vec!["0", "text"]);
+        assert_eq!(
+            tuple.get_data("text"),
+            Some(TokenMeta::Str(String::from("a")))
+        );
+        assert_eq!(round_trip(&tuple), Some(tuple));
+
+        let named = EnumToken::Named {
+            text: String::from("a"),
+        };
+        assert_eq!(named.get_all_name(), vec!["variant", "text"]);
+        assert_eq!(
+            named.get_data("variant"),
+            Some(TokenMeta::Str(String::from("Named")))
+        );
+        for token in [EnumToken::Empty, EnumToken::Level(3), named] {
+            assert_eq!(round_trip(&token).as_ref(), Some(&token));
+        }
+        let mut data = TokenData::new(&EnumToken::Empty);
+        data.data.insert(
+            String::from("variant"),
+            TokenMeta::Str(String::from("Other")),
+        );
+        assert_eq!(data.rebuild::<EnumToken>(), None);
+    }
+
+    #[test]
+    fn test_field_options() {
+        let token = OptionToken {
+            cache: vec!['a'],
+            level: 2,
+            count: 10,
+        };
+        assert_eq!(token.get_all_name(), vec!["level", "count"]);
+        assert_eq!(token.get_data("cache"), None);
+        assert_eq!(
+            token.get_data("count"),
+            Some(TokenMeta::Str(String::from("10")))
+        );
+        assert_eq!(
+            round_trip(&token),
+            Some(OptionToken {
+                cache: vec![],
+                level: 2,
+                count: 10,
+            })
+        );
+
+        let mut data = TokenData::new(&token);
+        data.data.remove("level");
+        let rebuilt = data.rebuild::<OptionToken>().unwrap();
+        assert_eq!(rebuilt.level, 0);
+        data.data.remove("count");
+        assert_eq!(data.rebuild::<OptionToken>(), None);
+    }
 }
@@ -0,0 +1,86 @@
+use full_token_derive_macro::FullToken;
+
+use super::{
+    super::token_trait::{FullToken, TokenTrait},
+    Plain,
+};
+
+/// 维基链接 `[[target#fragment|label]]` 与嵌入 `![[file]]`
+///
+/// 没有 `#` 或 `|` 部分时对应字段为空
+pub struct PartWiki;
+
+impl TokenTrait for PartWiki {
+    fn name(&self) -> &'static str {
+        "wiki"
+    }
+
+    fn to_full(&self, buff: &[char]) -> Box<dyn FullToken> {
+        let embed = buff[0] == '!';
+        let start = if embed { 3 } else { 2 };
+        let inner = String::from_iter(buff[start..buff.len() - 2].iter());
+        let (link, label) = inner.split_once('|').unwrap_or((&inner, ""));
+        let (target, fragment) = link.split_once('#').unwrap_or((link, ""));
+        // 没有链接目标时作为普通文本
+        if target.trim().is_empty() && fragment.trim().is_empty() {
+            return Plain::new_box(buff);
+        }
+        Box::new(Wiki {
+            target: target.trim().to_string(),
+            fragment: fragment.trim().to_string(),
+            label: label.trim().to_string(),
+            embed,
+        })
+    }
+}
+
+#[derive(FullToken)]
+#[token(name = "wiki", kind = "Wiki")]
+pub struct Wiki {
+    pub target: String,
+    pub fragment: String,
+    pub label: String,
+    pub embed: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lexical::{token_trait::FromTokenMeta, LexicalLoader, Token};
+
+    fn first(src: &str) -> (String, Option<[String; 4]>) {
+        let Token(t, raw) = LexicalLoader::new(src.chars()).next_token();
+        let data = (t.name() == "wiki").then(|| {
+            ["target", "fragment", "label", "embed"].map(|k| {
+                let data = t.get_data(k).unwrap();
+                String::from_token_meta(&data)
+                    .or_else(|| bool::from_token_meta(&data).map(|b| b.to_string()))
+                    .unwrap()
+            })
+        });
+        (raw, data)
+    }
+
+    #[test]
+    fn test_wiki() {
+        assert_eq!(
+            first("[[Page#Intro|the page]] x"),
+            (
+                String::from("[[Page#Intro|the page]]"),
+                Some(["Page", "Intro", "the page", "false"].map(String::from))
+            )
+        );
+        assert_eq!(
+            first("![[diagram.png]]"),
+            (
+                String::from("![[diagram.png]]"),
+                Some(["diagram.png", "", "", "true"].map(String::from))
+            )
+        );
+        // 未闭合时整体作为普通文本
+        assert_eq!(first("[[a] b").1, None);
+        assert_eq!(first("[[]]").1, None);
+        for src in ["[[ ]]", "[[#]]", "[[ # |x]]"] {
+            assert_eq!(first(src), (String::from(src), None));
+        }
+    }
+}
@@ -1,4 +1,7 @@
 mod interface;
 mod utils;
-mod lexical;
-mod syntax;
\ No newline at end of file
+pub mod lexical;
+pub mod check;
+pub mod lint;
+pub mod render;
+pub mod syntax;
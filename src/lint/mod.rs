@@ -0,0 +1,400 @@
+//! markdown 风格检查
+//!
+//! 每条规则实现 [`Rule`]，在同一份词法单元序列与文档树上运行，
+//! 可附带以文本编辑表示的自动修复。
+//! 文档中的 `<!-- lint-disable rule -->` 与 `<!-- lint-enable rule -->` 注释
+//! 关闭或重新开启其后各行的规则，不写规则名时作用于全部规则
+
+use std::{
+    collections::HashMap,
+    fmt,
+    ops::Range,
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+
+use crate::{
+    lexical::{LexicalLoader, Token},
+    syntax::{parse_blocks, Node, NodeKind, Options},
+};
+
+pub mod rules;
+
+/// 问题的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// 将源码中 `range` 范围（字节）替换为 `text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+impl Edit {
+    pub fn new(range: Range<usize>, text: impl Into<String>) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+
+    pub fn insert(offset: usize, text: impl Into<String>) -> Self {
+        Self::new(offset..offset, text)
+    }
+}
+
+/// 规则报告的问题，`line` 与 `column` 从 1 开始
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    /// 自动修复，为空时无法自动修复
+    pub fix: Vec<Edit>,
+}
+
+impl Diagnostic {
+    pub fn new<R: Rule + ?Sized>(
+        rule: &R,
+        line: usize,
+        column: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            rule: rule.id(),
+            severity: rule.severity(),
+            line,
+            column,
+            message: message.into(),
+            fix: Vec::new(),
+        }
+    }
+
+    /// 添加自动修复
+    pub fn fix(mut self, edit: Edit) -> Self {
+        self.fix.push(edit);
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {} {}: {}",
+            self.line, self.column, self.severity, self.rule, self.message
+        )
+    }
+}
+
+/// 检查规则，`Linter` 可以在线程间共享
+pub trait Rule: Send + Sync + UnwindSafe + RefUnwindSafe {
+    /// 规则名称，用于配置与 `lint-disable` 注释
+    fn id(&self) -> &'static str;
+    /// 默认的严重程度
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+    fn check(&self, ctx: &Context, out: &mut Vec<Diagnostic>);
+}
+
+/// 规则检查时可用的文档信息
+pub struct Context<'s> {
+    pub source: &'s str,
+    /// 词法单元与其在源码中的起始字节
+    pub tokens: Vec<(usize, Token)>,
+    /// 顶层块与其所在的行（从 0 开始）
+    pub blocks: Vec<(Node, Range<usize>)>,
+    lines: Vec<&'s str>,
+    // 每行起始字节
+    starts: Vec<usize>,
+}
+
+impl<'s> Context<'s> {
+    pub fn new(source: &'s str, opts: &Options) -> Self {
+        let mut offset = 0;
+        let tokens = LexicalLoader::new(source.chars())
+            .map(|token| {
+                let start = offset;
+                offset += token.1.len();
+                (start, token)
+            })
+            .collect();
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            source,
+            tokens,
+            blocks: parse_blocks(source, opts),
+            lines: source.lines().collect(),
+            starts,
+        }
+    }
+
+    /// 全部行，不含换行符
+    pub fn lines(&self) -> &[&'s str] {
+        &self.lines
+    }
+
+    /// 第 `line` 行（从 1 开始）的起始字节
+    pub fn line_start(&self, line: usize) -> usize {
+        self.starts
+            .get(line.saturating_sub(1))
+            .copied()
+            .unwrap_or(self.source.len())
+    }
+
+    /// 字节位置对应的行与列，均从 1 开始
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|start| *start <= offset);
+        let start = self.starts[line - 1];
+        let column = self.source[start..offset].chars().count() + 1;
+        (line, column)
+    }
+
+    /// 行与列（均从 1 开始）对应的字节位置
+    pub fn offset(&self, line: usize, column: usize) -> usize {
+        let start = self.line_start(line);
+        let text = self.lines.get(line - 1).copied().unwrap_or_default();
+        start
+            + text
+                .char_indices()
+                .nth(column - 1)
+                .map_or(text.len(), |(i, _)| i)
+    }
+
+    /// 第 `line` 行（从 1 开始）是否位于代码块或 front matter 中，这些行不按 markdown 检查
+    pub fn is_literal(&self, line: usize) -> bool {
+        self.blocks.iter().any(|(node, span)| {
+            matches!(
+                node.kind,
+                NodeKind::CodeBlock { .. } | NodeKind::FrontMatter { .. }
+            ) && span.contains(&(line - 1))
+        })
+    }
+}
+
+/// 运行一组规则的检查器
+pub struct Linter {
+    rules: Vec<Box<dyn Rule>>,
+    severities: HashMap<&'static str, Severity>,
+    opts: Options,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Linter {
+    /// 使用默认配置的全部内置规则
+    pub fn new() -> Self {
+        Self::empty().rules(rules::all())
+    }
+
+    /// 不含任何规则
+    pub fn empty() -> Self {
+        Self {
+            rules: Vec::new(),
+            severities: HashMap::new(),
+            opts: Options::default(),
+        }
+    }
+
+    /// 添加规则，替换同名的已有规则
+    pub fn rule<R: Rule + 'static>(self, rule: R) -> Self {
+        self.rules(vec![Box::new(rule)])
+    }
+
+    fn rules(mut self, rules: Vec<Box<dyn Rule>>) -> Self {
+        for rule in rules {
+            match self.rules.iter_mut().find(|r| r.id() == rule.id()) {
+                Some(old) => *old = rule,
+                None => self.rules.push(rule),
+            }
+        }
+        self
+    }
+
+    /// 移除规则
+    pub fn disable(mut self, id: &str) -> Self {
+        self.rules.retain(|rule| rule.id() != id);
+        self
+    }
+
+    /// 覆盖规则的严重程度
+    pub fn severity(mut self, id: &str, severity: Severity) -> Self {
+        if let Some(rule) = self.rules.iter().find(|r| r.id() == id) {
+            self.severities.insert(rule.id(), severity);
+        }
+        self
+    }
+
+    /// 设置解析文档使用的选项
+    pub fn options(mut self, opts: Options) -> Self {
+        self.opts = opts;
+        self
+    }
+
+    /// 检查文档，结果按位置排序
+    pub fn lint(&self, source: &str) -> Vec<Diagnostic> {
+        let ctx = Context::new(source, &self.opts);
+        let directives = directives(&ctx);
+        let mut out = Vec::new();
+        for rule in &self.rules {
+            let mut found = Vec::new();
+            rule.check(&ctx, &mut found);
+            out.extend(
+                found
+                    .into_iter()
+                    .filter(|d| is_enabled(&directives, d.rule, d.line)),
+            );
+        }
+        for diagnostic in &mut out {
+            if let Some(severity) = self.severities.get(diagnostic.rule) {
+                diagnostic.severity = *severity;
+            }
+        }
+        out.sort_by_key(|d| (d.line, d.column));
+        out
+    }
+}
+
+/// 应用自动修复，与已应用的修改重叠的编辑被忽略
+pub fn fix(source: &str, diagnostics: &[Diagnostic]) -> String {
+    let mut edits = diagnostics.iter().flat_map(|d| &d.fix).collect::<Vec<_>>();
+    edits.sort_by_key(|e| (e.range.start, e.range.end));
+    let mut out = String::with_capacity(source.len());
+    let mut last = 0;
+    for edit in edits {
+        if edit.range.start < last {
+            continue;
+        }
+        out.push_str(&source[last..edit.range.start]);
+        out.push_str(&edit.text);
+        last = edit.range.end;
+    }
+    out.push_str(&source[last..]);
+    out
+}
+
+/// `lint-disable` / `lint-enable` 注释
+struct Directive {
+    line: usize,
+    enable: bool,
+    // 为空时作用于全部规则
+    rules: Vec<String>,
+}
+
+fn directives(ctx: &Context) -> Vec<Directive> {
+    let mut out = Vec::new();
+    for (idx, line) in ctx.lines().iter().enumerate() {
+        if ctx.is_literal(idx + 1) {
+            continue;
+        }
+        let mut rest = *line;
+        while let Some(start) = rest.find("<!--") {
+            // 结束标记在 `<!--` 之后查找，`<!-->` 不是完整的注释
+            let body = &rest[start + 4..];
+            let Some(end) = body.find("-->") else {
+                break;
+            };
+            let mut words = body[..end].split_whitespace();
+            rest = &body[end + 3..];
+            let enable = match words.next() {
+                Some("lint-disable") => false,
+                Some("lint-enable") => true,
+                _ => continue,
+            };
+            out.push(Directive {
+                line: idx + 1,
+                enable,
+                rules: words.map(String::from).collect(),
+            });
+        }
+    }
+    out
+}
+
+fn is_enabled(directives: &[Directive], rule: &str, line: usize) -> bool {
+    directives
+        .iter()
+        .take_while(|d| d.line <= line)
+        .filter(|d| d.rules.is_empty() || d.rules.iter().any(|r| r == rule))
+        .last()
+        .is_none_or(|d| d.enable)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_disable() {
+        let source = "# A\n\n### B\n\n<!-- lint-disable heading-increment -->\n\n##### C\n\n\
+                      <!-- lint-enable -->\n\n####### not heading\n\n# A\n";
+        let found = Linter::new().lint(source);
+        let rules = found.iter().map(|d| (d.line, d.rule)).collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![(3, "heading-increment"), (13, "duplicate-heading")]
+        );
+
+        let found = Linter::empty()
+            .rule(rules::HeadingIncrement)
+            .severity("heading-increment", Severity::Error)
+            .lint(source);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].to_string(),
+            "3:1: error heading-increment: heading level jumps from h1 to h3"
+        );
+
+        // 未结束的注释不会越界
+        for source in ["a <!--> b\n", "a <!---> b\n"] {
+            Linter::new().lint(source);
+        }
+        let found = Linter::new().lint("<!-->lint-disable -->\n# A\n\n### B\n");
+        assert_eq!(found.len(), 1);
+    }
+
+    #[test]
+    fn test_thread_safe() {
+        fn check<T: Send + Sync + std::panic::UnwindSafe>() {}
+        check::<Options>();
+        check::<Linter>();
+        check::<crate::check::LinkChecker>();
+    }
+
+    #[test]
+    fn test_fix() {
+        let source = "# A\n\n### B\nline  \nnext see https://a.b/c.\n";
+        let found = Linter::new().lint(source);
+        assert_eq!(
+            fix(source, &found),
+            "# A\n\n## B\nline\\\nnext see <https://a.b/c>.\n"
+        );
+        assert_eq!(
+            Context::new(source, &Options::default()).position(7),
+            (3, 3)
+        );
+    }
+}
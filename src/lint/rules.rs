@@ -0,0 +1,461 @@
+//! 内置规则
+use std::collections::HashMap;
+
+use crate::{
+    check::locate,
+    lexical::token_trait::TokenKind,
+    syntax::{Node, NodeKind},
+};
+
+use super::{Context, Diagnostic, Edit, Rule, Severity};
+
+/// 默认配置的全部内置规则
+pub fn all() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(HeadingIncrement),
+        Box::new(DuplicateHeading),
+        Box::new(ListMarkerStyle::new()),
+        Box::new(HardBreakSpaces),
+        Box::new(ImageAltText),
+        Box::new(BareUrl),
+        Box::new(LineLength::new()),
+    ]
+}
+
+/// 标题级别一次只能增加一级，如 h1 之后不能直接出现 h3
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeadingIncrement;
+
+impl Rule for HeadingIncrement {
+    fn id(&self) -> &'static str {
+        "heading-increment"
+    }
+
+    fn check(&self, ctx: &Context, out: &mut Vec<Diagnostic>) {
+        let mut prev = None;
+        for (level, _, line) in headings(ctx) {
+            match prev {
+                Some(prev) if level > prev + 1 => {
+                    let mut diagnostic = Diagnostic::new(
+                        self,
+                        line,
+                        1,
+                        format!("heading level jumps from h{} to h{}", prev, level),
+                    );
+                    // 只修复 atx 标题
+                    let text = ctx.lines()[line - 1];
+                    let indent = text.len() - text.trim_start().len();
+                    if text[indent..].starts_with('#') {
+                        let start = ctx.line_start(line) + indent;
+                        diagnostic = diagnostic.fix(Edit::new(
+                            start..start + level as usize,
+                            "#".repeat(prev as usize + 1),
+                        ));
+                    }
+                    out.push(diagnostic);
+                }
+                _ => {}
+            }
+            prev = Some(level);
+        }
+    }
+}
+
+/// 标题文本不能重复
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DuplicateHeading;
+
+impl Rule for DuplicateHeading {
+    fn id(&self) -> &'static str {
+        "duplicate-heading"
+    }
+
+    fn check(&self, ctx: &Context, out: &mut Vec<Diagnostic>) {
+        let mut seen = HashMap::new();
+        for (_, text, line) in headings(ctx) {
+            match seen.get(&text) {
+                Some(first) => out.push(Diagnostic::new(
+                    self,
+                    line,
+                    1,
+                    format!("duplicate heading `{}`, first used on line {}", text, first),
+                )),
+                None => {
+                    seen.insert(text, line);
+                }
+            }
+        }
+    }
+}
+
+/// 无序列表标记保持一致，默认与文档中第一个标记相同
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListMarkerStyle {
+    marker: Option<char>,
+}
+
+impl ListMarkerStyle {
+    pub fn new() -> Self {
+        Self { marker: None }
+    }
+
+    /// 指定使用的标记，`-`、`*` 或 `+`
+    pub fn marker(mut self, marker: char) -> Self {
+        self.marker = Some(marker);
+        self
+    }
+}
+
+impl Rule for ListMarkerStyle {
+    fn id(&self) -> &'static str {
+        "list-marker-style"
+    }
+
+    fn check(&self, ctx: &Context, out: &mut Vec<Diagnostic>) {
+        let mut expected = self.marker;
+        for (offset, token) in &ctx.tokens {
+            if token.0.kind() != TokenKind::UnorderList {
+                continue;
+            }
+            let (line, column) = ctx.position(*offset);
+            let Some(marker) = token.1.chars().next() else {
+                continue;
+            };
+            // 分隔线同样以 `*`、`-` 开头
+            let text = ctx.lines()[line - 1];
+            let is_break = text.chars().filter(|c| *c == marker).count() >= 3
+                && text.chars().all(|c| c == marker || c == ' ' || c == '\t');
+            if is_break || ctx.is_literal(line) {
+                continue;
+            }
+            match expected {
+                None => expected = Some(marker),
+                Some(expected) if expected != marker => out.push(
+                    Diagnostic::new(
+                        self,
+                        line,
+                        column,
+                        format!("list marker `{}` should be `{}`", marker, expected),
+                    )
+                    .fix(Edit::new(
+                        *offset..*offset + marker.len_utf8(),
+                        expected.to_string(),
+                    )),
+                ),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// 不使用行尾空格表示强制换行，改用 `\`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HardBreakSpaces;
+
+impl Rule for HardBreakSpaces {
+    fn id(&self) -> &'static str {
+        "hard-break-spaces"
+    }
+
+    fn check(&self, ctx: &Context, out: &mut Vec<Diagnostic>) {
+        for (offset, token) in &ctx.tokens {
+            if token.0.kind() != TokenKind::HardBreak || !token.1.starts_with([' ', '\t']) {
+                continue;
+            }
+            let (line, column) = ctx.position(*offset);
+            if ctx.is_literal(line) {
+                continue;
+            }
+            let spaces = *offset..*offset + token.1.trim_end_matches(['\r', '\n']).len();
+            // 段落末尾与标题中的行尾空格不产生换行，直接删除
+            let next = ctx.lines().get(line).copied().unwrap_or_default();
+            let no_effect =
+                next.trim().is_empty() || ctx.lines()[line - 1].trim_start().starts_with('#');
+            let diagnostic = if no_effect {
+                Diagnostic::new(self, line, column, "trailing spaces have no effect")
+                    .fix(Edit::new(spaces, ""))
+            } else {
+                Diagnostic::new(
+                    self,
+                    line,
+                    column,
+                    "trailing spaces used as a hard break, use `\\` instead",
+                )
+                .fix(Edit::new(spaces, "\\"))
+            };
+            out.push(diagnostic);
+        }
+    }
+}
+
+/// 图片需要替代文本
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageAltText;
+
+impl Rule for ImageAltText {
+    fn id(&self) -> &'static str {
+        "image-alt-text"
+    }
+
+    fn check(&self, ctx: &Context, out: &mut Vec<Diagnostic>) {
+        for (node, span) in &ctx.blocks {
+            let mut cursor = (span.start, 0);
+            walk(node, &mut |node| {
+                if let NodeKind::Image { destination, .. } = &node.kind {
+                    let (line, column) = locate(ctx.lines(), span.clone(), &mut cursor, "![");
+                    if node.text_content().trim().is_empty() {
+                        out.push(Diagnostic::new(
+                            self,
+                            line,
+                            column,
+                            format!("image `{}` has no alt text", destination),
+                        ));
+                    }
+                }
+                true
+            });
+        }
+    }
+}
+
+/// 网址需要写为链接或 `<url>`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BareUrl;
+
+impl Rule for BareUrl {
+    fn id(&self) -> &'static str {
+        "bare-url"
+    }
+
+    fn check(&self, ctx: &Context, out: &mut Vec<Diagnostic>) {
+        for (node, span) in &ctx.blocks {
+            let mut cursor = (span.start, 0);
+            walk(node, &mut |node| {
+                if matches!(
+                    node.kind,
+                    NodeKind::Link { .. } | NodeKind::Image { .. } | NodeKind::WikiLink { .. }
+                ) {
+                    return false;
+                }
+                // 相邻的文本节点合并后查找
+                for text in text_runs(&node.children) {
+                    for url in bare_urls(&text) {
+                        let (line, column) = locate(ctx.lines(), span.clone(), &mut cursor, url);
+                        let mut diagnostic =
+                            Diagnostic::new(self, line, column, format!("bare url `{}`", url));
+                        let start = ctx.offset(line, column);
+                        if ctx.source[start..].starts_with(url) {
+                            diagnostic = diagnostic
+                                .fix(Edit::insert(start, "<"))
+                                .fix(Edit::insert(start + url.len(), ">"));
+                        }
+                        out.push(diagnostic);
+                    }
+                }
+                true
+            });
+        }
+    }
+}
+
+/// 行的长度限制，超出部分不含空白（如长网址）时不报告
+#[derive(Debug, Clone, Copy)]
+pub struct LineLength {
+    max: usize,
+    code_blocks: bool,
+}
+
+impl Default for LineLength {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineLength {
+    pub fn new() -> Self {
+        Self {
+            max: 80,
+            code_blocks: false,
+        }
+    }
+
+    /// 最大长度，默认为 80 个字符
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// 是否检查代码块中的行，默认不检查
+    pub fn code_blocks(mut self, code_blocks: bool) -> Self {
+        self.code_blocks = code_blocks;
+        self
+    }
+}
+
+impl Rule for LineLength {
+    fn id(&self) -> &'static str {
+        "line-length"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, ctx: &Context, out: &mut Vec<Diagnostic>) {
+        for (idx, text) in ctx.lines().iter().enumerate() {
+            let len = text.chars().count();
+            if len <= self.max || (!self.code_blocks && ctx.is_literal(idx + 1)) {
+                continue;
+            }
+            let rest = text.chars().skip(self.max).collect::<String>();
+            if !rest.contains(char::is_whitespace) {
+                continue;
+            }
+            out.push(Diagnostic::new(
+                self,
+                idx + 1,
+                self.max + 1,
+                format!("line is {} characters long, limit is {}", len, self.max),
+            ));
+        }
+    }
+}
+
+/// 先序遍历，`f` 返回 `false` 时不进入子节点
+fn walk<'a>(node: &'a Node, f: &mut impl FnMut(&'a Node) -> bool) {
+    if f(node) {
+        for child in &node.children {
+            walk(child, f);
+        }
+    }
+}
+
+/// 文档中的标题：级别、文本与所在行（从 1 开始）
+fn headings(ctx: &Context) -> Vec<(u8, String, usize)> {
+    let mut out = Vec::new();
+    for (block, span) in &ctx.blocks {
+        let mut cursor = (span.start, 0);
+        walk(block, &mut |node| {
+            if let NodeKind::Heading { level } = node.kind {
+                let text = node.text_content().trim().to_string();
+                let line = if std::ptr::eq(node, block) {
+                    span.start + 1
+                } else {
+                    locate(ctx.lines(), span.clone(), &mut cursor, &text).0
+                };
+                out.push((level, text, line));
+            }
+            true
+        });
+    }
+    out
+}
+
+/// 连续文本节点拼接后的文本
+fn text_runs(nodes: &[Node]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut run = None::<String>;
+    for node in nodes {
+        match &node.kind {
+            NodeKind::Text { value } => run.get_or_insert_with(String::new).push_str(value),
+            _ => out.extend(run.take()),
+        }
+    }
+    out.extend(run);
+    out
+}
+
+/// 文本中以 `http://` 或 `https://` 开头的网址，不含结尾的标点
+fn bare_urls(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut from = 0;
+    while let Some(pos) = ["http://", "https://"]
+        .iter()
+        .filter_map(|scheme| text[from..].find(scheme))
+        .min()
+    {
+        let start = from + pos;
+        let boundary = text[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+        let len = text[start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\''))
+            .unwrap_or(text.len() - start);
+        let url = text[start..start + len].trim_end_matches(['.', ',', ':', ';', '!', '?', ')']);
+        if boundary && !url.ends_with("//") {
+            out.push(url);
+        }
+        from = start + len.max(1);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::lint::Linter;
+
+    use super::*;
+
+    fn lint<R: Rule + 'static>(rule: R, source: &str) -> Vec<(usize, usize, String)> {
+        Linter::empty()
+            .rule(rule)
+            .lint(source)
+            .into_iter()
+            .map(|d| (d.line, d.column, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(
+            lint(HeadingIncrement, "# A\n### B\n> #### C\n#### D\n"),
+            vec![(2, 1, String::from("heading level jumps from h1 to h3"))]
+        );
+        assert_eq!(
+            lint(DuplicateHeading, "# A\n\n- ## A\n"),
+            vec![(
+                3,
+                1,
+                String::from("duplicate heading `A`, first used on line 1")
+            )]
+        );
+        assert_eq!(
+            lint(ListMarkerStyle::new(), "- a\n* b\n\n* * *\n```\n+ c\n```\n"),
+            vec![(2, 1, String::from("list marker `*` should be `-`"))]
+        );
+        assert_eq!(
+            lint(ImageAltText, "![](a.png) ![b](b.png)\n> ![ ](c.png)\n"),
+            vec![
+                (1, 1, String::from("image `a.png` has no alt text")),
+                (2, 3, String::from("image `c.png` has no alt text")),
+            ]
+        );
+        assert_eq!(
+            lint(
+                BareUrl,
+                "see https://a.b/x_y, [l](http://c.d) `http://e.f`\n"
+            ),
+            vec![(1, 5, String::from("bare url `https://a.b/x_y`"))]
+        );
+        assert_eq!(
+            lint(
+                LineLength::new().max(10),
+                "short\nthis line is long\nhttps://long.url/path\n"
+            ),
+            vec![(
+                2,
+                11,
+                String::from("line is 17 characters long, limit is 10")
+            )]
+        );
+    }
+
+    #[test]
+    fn test_bare_urls() {
+        assert_eq!(
+            bare_urls("a http://x.y/z). xhttps://no (https://q.r)"),
+            vec!["http://x.y/z", "https://q.r"]
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! markdown_analyze 命令行工具
+//!
+//! 每个文件的结果按行输出，便于脚本处理。
+//! 退出码：`0` 正常，`1` 发现问题（`lint` 有警告以上的问题或 `fmt --check` 有未格式化文件），`2` 参数或读写错误
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+    process::ExitCode,
+};
+
+use markdown_analyze::{
+    check::LinkChecker,
+    lexical::{LexicalLoader, Token},
+    lint::{self, Linter, Severity},
+    render::{html::HtmlRenderer, markdown::MarkdownRenderer, safe::SafeMode},
+    syntax::{json, parse_with, Options},
+};
+use serde_json::json;
+
+const USAGE: &str = "\
+usage: markdown_analyze <command> [options] [FILE...]
+
+commands:
+    tokens    print lexer tokens as JSON lines
+    ast       print the versioned syntax tree of each file as a JSON line
+    html      render html
+    fmt       print normalized markdown
+    lint      check style rules and local links
+    schema    print the JSON Schema of the `ast` output
+
+options:
+    -e, --extensions    enable all optional syntax extensions
+    --safe              (html) sanitize raw html and urls
+    --check             (fmt) list files that are not formatted, write nothing
+    --fix               (lint) apply automatic fixes, stdin input is written to stdout
+    -h, --help          print this help
+
+Reads standard input when no FILE or `-` is given.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Tokens,
+    Ast,
+    Html,
+    Fmt,
+    Lint,
+    Schema,
+}
+
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    files: Vec<String>,
+    extensions: bool,
+    safe: bool,
+    check: bool,
+    fix: bool,
+}
+
+impl Args {
+    /// 解析命令行参数，失败时返回错误信息
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let command = match args.next().as_deref() {
+            Some("tokens") => Command::Tokens,
+            Some("ast") => Command::Ast,
+            Some("html") => Command::Html,
+            Some("fmt") => Command::Fmt,
+            Some("lint") => Command::Lint,
+            Some("schema") => Command::Schema,
+            Some(other) => return Err(format!("unknown command `{}`", other)),
+            None => return Err(String::from("missing command")),
+        };
+        let mut this = Self {
+            command,
+            files: Vec::new(),
+            extensions: false,
+            safe: false,
+            check: false,
+            fix: false,
+        };
+        let mut only_files = false;
+        for arg in args {
+            if only_files || arg == "-" || !arg.starts_with('-') {
+                this.files.push(arg);
+                continue;
+            }
+            match arg.as_str() {
+                "--" => only_files = true,
+                "-e" | "--extensions" => this.extensions = true,
+                "--safe" if command == Command::Html => this.safe = true,
+                "--check" if command == Command::Fmt => this.check = true,
+                "--fix" if command == Command::Lint => this.fix = true,
+                _ => return Err(format!("unknown option `{}`", arg)),
+            }
+        }
+        if this.files.is_empty() {
+            this.files.push(String::from("-"));
+        }
+        Ok(this)
+    }
+
+    fn options(&self) -> Options {
+        let opts = Options::new();
+        if self.extensions {
+            opts.mark(true)
+                .insert(true)
+                .superscript(true)
+                .subscript(true)
+                .strikethrough(true)
+                .abbreviations(true)
+        } else {
+            opts
+        }
+    }
+}
+
+fn read_input(file: &str) -> io::Result<String> {
+    if file == "-" {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        Ok(buf)
+    } else {
+        fs::read_to_string(file)
+    }
+}
+
+/// 处理单个文件，返回是否发现问题
+fn run_file(args: &Args, file: &str, out: &mut impl Write) -> io::Result<bool> {
+    let source = read_input(file)?;
+    let opts = args.options();
+    match args.command {
+        Command::Tokens => {
+            for Token(token, raw) in LexicalLoader::new(source.chars()) {
+                let line = json!({
+                    "file": file,
+                    "name": token.name(),
+                    "raw": raw,
+                    "data": &*token,
+                });
+                writeln!(out, "{}", line)?;
+            }
+        }
+        Command::Ast => {
+            let line = json!({ "file": file, "ast": json::export(&source, &opts) });
+            writeln!(out, "{}", line)?;
+        }
+        Command::Html => {
+            let mut renderer = HtmlRenderer::new();
+            if args.safe {
+                renderer = renderer.safe_mode(SafeMode::new());
+            }
+            write!(out, "{}", renderer.render(&parse_with(&source, &opts)))?;
+        }
+        Command::Fmt => {
+            let formatted = MarkdownRenderer::new().render(&parse_with(&source, &opts));
+            if !args.check {
+                write!(out, "{}", formatted)?;
+            } else if formatted != source {
+                writeln!(out, "{}", file)?;
+                return Ok(true);
+            }
+        }
+        Command::Lint => {
+            let base = match file {
+                "-" => Path::new("."),
+                file => Path::new(file).parent().unwrap_or(Path::new(".")),
+            };
+            let mut diagnostics = Linter::new().options(opts.clone()).lint(&source);
+            if args.fix {
+                let fixed = lint::fix(&source, &diagnostics);
+                if file == "-" {
+                    write!(out, "{}", fixed)?;
+                } else if fixed != source {
+                    fs::write(file, fixed)?;
+                }
+                diagnostics.retain(|d| d.fix.is_empty());
+            }
+            // 修复结果输出到标准输出时，问题只输出到标准错误
+            let mut report = |line: String| {
+                if args.fix && file == "-" {
+                    eprintln!("{}", line);
+                    Ok(())
+                } else {
+                    writeln!(out, "{}", line)
+                }
+            };
+            let mut failed = false;
+            for diagnostic in &diagnostics {
+                failed |= diagnostic.severity >= Severity::Warning;
+                report(format!("{}:{}", file, diagnostic))?;
+            }
+            let links = LinkChecker::new(base).options(opts).check(&source);
+            for issue in &links.issues {
+                report(format!("{}:{}", file, issue))?;
+            }
+            return Ok(failed || !links.is_ok());
+        }
+        Command::Schema => unreachable!("schema does not read input"),
+    }
+    Ok(false)
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    let args = match Args::parse(args.into_iter()) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if args.command == Command::Schema {
+        let schema = serde_json::to_string_pretty(&json::schema()).expect("schema serializes");
+        println!("{}", schema);
+        return ExitCode::SUCCESS;
+    }
+
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    let mut failed = false;
+    for file in &args.files {
+        match run_file(&args, file, &mut out) {
+            Ok(found) => failed |= found,
+            Err(err) => {
+                let _ = out.flush();
+                eprintln!("error: {}: {}", file, err);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    if out.flush().is_err() {
+        return ExitCode::from(2);
+    }
+    if failed {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
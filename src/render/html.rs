@@ -0,0 +1,490 @@
+use crate::syntax::{Attributes, Node, NodeKind};
+
+use super::{admonition_title, safe::SafeMode};
+
+/// 软换行的输出方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SoftBreak {
+    /// 输出换行符
+    #[default]
+    Newline,
+    /// 输出空格
+    Space,
+    /// 与强制换行相同，输出 `<br />`
+    Br,
+}
+
+/// 将文档树输出为 html
+#[derive(Debug, Clone, Default)]
+pub struct HtmlRenderer {
+    // 安全模式，为 `None` 时原样输出 html
+    safe: Option<SafeMode>,
+    soft_break: SoftBreak,
+}
+
+impl HtmlRenderer {
+    pub fn new() -> Self {
+        Self {
+            safe: None,
+            soft_break: SoftBreak::Newline,
+        }
+    }
+
+    /// 启用安全模式
+    pub fn safe_mode(mut self, mode: SafeMode) -> Self {
+        self.safe = Some(mode);
+        self
+    }
+
+    /// 设置软换行的输出方式
+    pub fn soft_break(mut self, soft_break: SoftBreak) -> Self {
+        self.soft_break = soft_break;
+        self
+    }
+
+    pub fn render(&self, node: &Node) -> String {
+        let mut out = String::with_capacity(256);
+        self.write_node(node, &mut out);
+        out
+    }
+
+    fn write_children(&self, node: &Node, out: &mut String) {
+        for child in &node.children {
+            self.write_node(child, out);
+        }
+    }
+
+    fn write_node(&self, node: &Node, out: &mut String) {
+        match &node.kind {
+            NodeKind::Document => self.write_children(node, out),
+            NodeKind::FrontMatter { .. } | NodeKind::Abbreviation { .. } => {}
+            NodeKind::Paragraph => {
+                self.open_tag("p", &node.attrs, "", out);
+                self.write_children(node, out);
+                out.push_str("</p>\n");
+            }
+            NodeKind::Heading { level } => {
+                self.open_tag(&format!("h{}", level), &node.attrs, "", out);
+                self.write_children(node, out);
+                out.push_str(&format!("</h{}>\n", level));
+            }
+            NodeKind::ThematicBreak => {
+                out.push_str("<hr");
+                self.write_attrs("hr", &node.attrs, "", out);
+                out.push_str(" />\n");
+            }
+            NodeKind::BlockQuote => {
+                self.open_tag("blockquote", &node.attrs, "", out);
+                out.push('\n');
+                self.write_children(node, out);
+                out.push_str("</blockquote>\n");
+            }
+            NodeKind::Admonition { kind, title } => {
+                self.open_tag("div", &node.attrs, &format!("admonition {}", kind), out);
+                out.push('\n');
+                out.push_str(&format!(
+                    "<p class=\"admonition-title\">{}</p>\n",
+                    escape_html(&admonition_title(kind, title))
+                ));
+                self.write_children(node, out);
+                out.push_str("</div>\n");
+            }
+            NodeKind::List { start, tight, .. } => {
+                let tag = if start.is_some() { "ol" } else { "ul" };
+                out.push('<');
+                out.push_str(tag);
+                self.write_attrs(tag, &node.attrs, "", out);
+                if let Some(start) = start.filter(|s| *s != 1) {
+                    out.push_str(&format!(" start=\"{}\"", start));
+                }
+                out.push_str(">\n");
+                for item in &node.children {
+                    self.write_item(item, *tight, out);
+                }
+                out.push_str(&format!("</{}>\n", tag));
+            }
+            NodeKind::ListItem => self.write_item(node, false, out),
+            NodeKind::CodeBlock { info, literal } => {
+                self.open_tag("pre", &node.attrs, "", out);
+                out.push_str("<code");
+                if let Some(lang) = info.split_whitespace().next() {
+                    out.push_str(&format!(" class=\"language-{}\"", escape_html(lang)));
+                }
+                out.push('>');
+                out.push_str(&escape_html(literal));
+                out.push_str("</code></pre>\n");
+            }
+            // html 原样输出，安全模式下过滤
+            NodeKind::HtmlBlock { literal } => {
+                self.write_raw(literal, out);
+                if !literal.ends_with('\n') {
+                    out.push('\n');
+                }
+            }
+            NodeKind::HtmlInline { literal } => self.write_raw(literal, out),
+            NodeKind::Abbr { title } => {
+                out.push_str("<abbr");
+                if !title.is_empty() {
+                    out.push_str(&format!(" title=\"{}\"", escape_html(title)));
+                }
+                out.push('>');
+                self.write_children(node, out);
+                out.push_str("</abbr>");
+            }
+
+            NodeKind::Text { value } => out.push_str(&escape_html(value)),
+            NodeKind::Code { literal } => {
+                out.push_str("<code>");
+                out.push_str(&escape_html(literal));
+                out.push_str("</code>");
+            }
+            // 保留 TeX 定界符，交由 MathJax/KaTeX 处理
+            NodeKind::Math { literal, display } => {
+                let (class, open, close) = if *display {
+                    ("display", "\\[", "\\]")
+                } else {
+                    ("inline", "\\(", "\\)")
+                };
+                out.push_str(&format!(
+                    "<span class=\"math {}\">{}{}{}</span>",
+                    class,
+                    open,
+                    escape_html(literal),
+                    close
+                ));
+            }
+            NodeKind::Entity { value, .. } => out.push_str(&escape_html(value)),
+            NodeKind::HardBreak => out.push_str("<br />\n"),
+            NodeKind::SoftBreak => match self.soft_break {
+                SoftBreak::Newline => out.push('\n'),
+                SoftBreak::Space => out.push(' '),
+                SoftBreak::Br => out.push_str("<br />\n"),
+            },
+            NodeKind::Emphasis => {
+                out.push_str("<em>");
+                self.write_children(node, out);
+                out.push_str("</em>");
+            }
+            NodeKind::Strong => {
+                out.push_str("<strong>");
+                self.write_children(node, out);
+                out.push_str("</strong>");
+            }
+            NodeKind::Mark
+            | NodeKind::Insert
+            | NodeKind::Superscript
+            | NodeKind::Subscript
+            | NodeKind::Strikethrough => {
+                let tag = match node.kind {
+                    NodeKind::Mark => "mark",
+                    NodeKind::Insert => "ins",
+                    NodeKind::Superscript => "sup",
+                    NodeKind::Subscript => "sub",
+                    _ => "del",
+                };
+                out.push_str(&format!("<{}>", tag));
+                self.write_children(node, out);
+                out.push_str(&format!("</{}>", tag));
+            }
+            NodeKind::Link { destination, title } => {
+                out.push_str("<a");
+                self.write_url("href", destination, out);
+                self.write_attrs("a", &node.attrs, "", out);
+                if !title.is_empty() {
+                    out.push_str(&format!(" title=\"{}\"", escape_html(title)));
+                }
+                if let Some(rel) = self.safe.as_ref().and_then(|s| s.rel_for(destination)) {
+                    if self.is_url_allowed(destination) {
+                        out.push_str(&format!(" rel=\"{}\"", rel));
+                    }
+                }
+                out.push('>');
+                self.write_children(node, out);
+                out.push_str("</a>");
+            }
+            NodeKind::Image { destination, title } => {
+                out.push_str("<img");
+                self.write_url("src", destination, out);
+                out.push_str(&format!(" alt=\"{}\"", escape_html(&node.text_content())));
+                self.write_attrs("img", &node.attrs, "", out);
+                if !title.is_empty() {
+                    out.push_str(&format!(" title=\"{}\"", escape_html(title)));
+                }
+                out.push_str(" />");
+            }
+            // 不存在的目标输出为带 missing 类的 span
+            NodeKind::WikiLink {
+                destination: None, ..
+            } => {
+                self.open_tag("span", &node.attrs, "wikilink missing", out);
+                self.write_children(node, out);
+                out.push_str("</span>");
+            }
+            NodeKind::WikiLink {
+                destination: Some(destination),
+                embed: true,
+                ..
+            } => {
+                out.push_str("<img");
+                self.write_url("src", destination, out);
+                out.push_str(&format!(" alt=\"{}\"", escape_html(&node.text_content())));
+                self.write_attrs("img", &node.attrs, "wikilink", out);
+                out.push_str(" />");
+            }
+            NodeKind::WikiLink {
+                destination: Some(destination),
+                ..
+            } => {
+                out.push_str("<a");
+                self.write_url("href", destination, out);
+                self.write_attrs("a", &node.attrs, "wikilink", out);
+                out.push('>');
+                self.write_children(node, out);
+                out.push_str("</a>");
+            }
+        }
+    }
+
+    /// 输出开始标签，`class` 为元素固有的类名
+    fn open_tag(&self, tag: &str, attrs: &Attributes, class: &str, out: &mut String) {
+        out.push('<');
+        out.push_str(tag);
+        self.write_attrs(tag, attrs, class, out);
+        out.push('>');
+    }
+
+    /// 输出属性块中的属性，安全模式下只输出白名单内的属性
+    fn write_attrs(&self, tag: &str, attrs: &Attributes, class: &str, out: &mut String) {
+        if let Some(id) = &attrs.id {
+            out.push_str(&format!(" id=\"{}\"", escape_html(id)));
+        }
+        let classes = Some(class)
+            .filter(|c| !c.is_empty())
+            .into_iter()
+            .chain(attrs.classes.iter().map(String::as_str))
+            .collect::<Vec<_>>();
+        if !classes.is_empty() {
+            out.push_str(&format!(" class=\"{}\"", escape_html(&classes.join(" "))));
+        }
+        for (name, value) in &attrs.pairs {
+            if self
+                .safe
+                .as_ref()
+                .is_some_and(|s| !s.is_attr_allowed(tag, name))
+            {
+                continue;
+            }
+            out.push_str(&format!(" {}=\"{}\"", name, escape_html(value)));
+        }
+    }
+
+    /// 紧凑列表中的段落不输出 `<p>`
+    fn write_item(&self, item: &Node, tight: bool, out: &mut String) {
+        out.push_str("<li>");
+        for child in &item.children {
+            if tight && child.kind == NodeKind::Paragraph {
+                self.write_children(child, out);
+            } else {
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                self.write_node(child, out);
+            }
+        }
+        out.push_str("</li>\n");
+    }
+
+    fn write_raw(&self, literal: &str, out: &mut String) {
+        match &self.safe {
+            Some(safe) => out.push_str(&safe.sanitize(literal)),
+            None => out.push_str(literal),
+        }
+    }
+
+    fn is_url_allowed(&self, url: &str) -> bool {
+        self.safe
+            .as_ref()
+            .map(|s| s.is_url_allowed(url))
+            .unwrap_or(true)
+    }
+
+    /// 输出链接属性，安全模式下不允许的协议直接省略
+    fn write_url(&self, attr: &str, url: &str, out: &mut String) {
+        if self.is_url_allowed(url) {
+            out.push_str(&format!(" {}=\"{}\"", attr, escape_html(url)));
+        }
+    }
+}
+
+/// 转义 html 特殊字符
+pub fn escape_html(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    for c in src.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::syntax::{parse, parse_with, Options, Toc};
+
+    use super::*;
+
+    fn to_html(src: &str) -> String {
+        HtmlRenderer::new().render(&parse(src))
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            to_html("# a & b\n\n*em* [l](/u \"t\")\n"),
+            "<h1>a &amp; b</h1>\n<p><em>em</em> <a href=\"/u\" title=\"t\">l</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_html_verbatim() {
+        assert_eq!(
+            to_html("<div class=\"x\">\n<b>raw</b>\n</div>\n\na <kbd>Ctrl</kbd> <https://a.b>"),
+            "<div class=\"x\">\n<b>raw</b>\n</div>\n\
+             <p>a <kbd>Ctrl</kbd> <a href=\"https://a.b\">https://a.b</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_soft_break() {
+        let doc = parse("a\nb  \nc");
+        assert_eq!(HtmlRenderer::new().render(&doc), "<p>a\nb<br />\nc</p>\n");
+        assert_eq!(
+            HtmlRenderer::new()
+                .soft_break(SoftBreak::Space)
+                .render(&doc),
+            "<p>a b<br />\nc</p>\n"
+        );
+        assert_eq!(
+            HtmlRenderer::new().soft_break(SoftBreak::Br).render(&doc),
+            "<p>a<br />\nb<br />\nc</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_list() {
+        assert_eq!(
+            to_html("- a\n  - b\n\n7) c\n\n   d\n8) e"),
+            "<ul>\n<li>a\n<ul>\n<li>b</li>\n</ul>\n</li>\n</ul>\n\
+             <ol start=\"7\">\n<li>\n<p>c</p>\n<p>d</p>\n</li>\n<li>\n<p>e</p>\n</li>\n</ol>\n"
+        );
+    }
+
+    #[test]
+    fn test_admonition() {
+        assert_eq!(
+            to_html("> [!WARNING]\n> *careful*\n\n::: tip Use <b>\nx\n:::"),
+            "<div class=\"admonition warning\">\n<p class=\"admonition-title\">Warning</p>\n\
+             <p><em>careful</em></p>\n</div>\n\
+             <div class=\"admonition tip\">\n<p class=\"admonition-title\">Use &lt;b&gt;</p>\n\
+             <p>x</p>\n</div>\n"
+        );
+    }
+
+    #[test]
+    fn test_wiki_link() {
+        let opts = Options::new().wiki_resolver(|target: &str, fragment: &str| {
+            (target != "Gone").then(|| format!("/wiki/{}.html#{}", target, fragment))
+        });
+        assert_eq!(
+            HtmlRenderer::new().render(&parse_with("[[Home#top|home]] [[Gone]] ![[a.png]]", &opts)),
+            "<p><a href=\"/wiki/Home.html#top\" class=\"wikilink\">home</a> \
+             <span class=\"wikilink missing\">Gone</span> \
+             <img src=\"/wiki/a.png.html#\" alt=\"a.png\" class=\"wikilink\" /></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_inline_extensions() {
+        let opts = Options::new()
+            .mark(true)
+            .insert(true)
+            .superscript(true)
+            .subscript(true)
+            .strikethrough(true);
+        assert_eq!(
+            HtmlRenderer::new().render(&parse_with("H~2~O x^2^ ==m== ++i++ ~~s~~", &opts)),
+            "<p>H<sub>2</sub>O x<sup>2</sup> <mark>m</mark> <ins>i</ins> <del>s</del></p>\n"
+        );
+        assert_eq!(to_html("H~2~O ==m=="), "<p>H~2~O ==m==</p>\n");
+    }
+
+    #[test]
+    fn test_abbr() {
+        let opts = Options::new().abbreviations(true);
+        assert_eq!(
+            HtmlRenderer::new().render(&parse_with(
+                "*[A&B]: \"x\" <y>\nA&B and RSS\n*[RSS]:",
+                &opts
+            )),
+            "<p><abbr title=\"&quot;x&quot; &lt;y&gt;\">A&amp;B</abbr> and <abbr>RSS</abbr></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_toc() {
+        let html = to_html("[TOC]\n\n# A\n## B [x]\n\n<!-- toc -->\n\n## B [x]");
+        let toc = "<ul class=\"toc\">\n<li><a href=\"#a\">A</a>\n\
+                   <ul>\n<li><a href=\"#b-x\">B [x]</a></li>\n\
+                   <li><a href=\"#b-x-1\">B [x]</a></li>\n</ul>\n</li>\n</ul>\n";
+        assert_eq!(
+            html,
+            format!(
+                "{toc}<h1 id=\"a\">A</h1>\n<h2 id=\"b-x\">B [x]</h2>\n\
+                 {toc}<h2 id=\"b-x-1\">B [x]</h2>\n"
+            )
+        );
+        let doc = parse_with(
+            "[toc]\n# A\n## B",
+            &Options::new().toc(Toc::new().min_level(2)),
+        );
+        assert_eq!(doc.children[0].children.len(), 1);
+    }
+
+    #[test]
+    fn test_math() {
+        assert_eq!(
+            to_html("$a<b$ $$x$$"),
+            "<p><span class=\"math inline\">\\(a&lt;b\\)</span> \
+             <span class=\"math display\">\\[x\\]</span></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_entity() {
+        assert_eq!(
+            to_html("&copy; &amp;amp; &#0; &nosuch; [a](/x?a=1&amp;b=2 \"&quot;\")"),
+            "<p>\u{A9} &amp;amp; \u{FFFD} &amp;nosuch; \
+             <a href=\"/x?a=1&amp;b=2\" title=\"&quot;\">a</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_attributes() {
+        let src = "# T {#t .a onclick=x}\n\n[l](/u){.b title=t}\n{.p}";
+        assert_eq!(
+            to_html(src),
+            "<h1 id=\"t\" class=\"a\" onclick=\"x\">T</h1>\n\
+             <p class=\"p\"><a href=\"/u\" class=\"b\" title=\"t\">l</a></p>\n"
+        );
+        let safe = HtmlRenderer::new().safe_mode(SafeMode::new());
+        assert_eq!(
+            safe.render(&parse(src)),
+            "<h1 id=\"t\" class=\"a\">T</h1>\n\
+             <p class=\"p\"><a href=\"/u\" class=\"b\" title=\"t\">l</a></p>\n"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use super::admonition_title;
+use crate::syntax::{Node, NodeKind};
+
+/// 将文档树输出为 LaTeX 正文，不包含导言区
+///
+/// 链接与图片依赖 `hyperref` 与 `graphicx` 宏包，
+/// 高亮依赖 `soul`，插入与删除线依赖 `ulem`
+#[derive(Debug, Clone, Default)]
+pub struct LatexRenderer;
+
+impl LatexRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, node: &Node) -> String {
+        let mut out = String::with_capacity(256);
+        self.write_node(node, &mut out);
+        out
+    }
+
+    fn write_children(&self, node: &Node, out: &mut String) {
+        for child in &node.children {
+            self.write_node(child, out);
+        }
+    }
+
+    fn write_node(&self, node: &Node, out: &mut String) {
+        match &node.kind {
+            NodeKind::Document => self.write_children(node, out),
+            NodeKind::FrontMatter { .. }
+            | NodeKind::HtmlBlock { .. }
+            | NodeKind::Abbreviation { .. } => {}
+            NodeKind::Paragraph => {
+                self.write_children(node, out);
+                out.push_str("\n\n");
+            }
+            NodeKind::Heading { level } => {
+                let command = match level {
+                    1 => "section",
+                    2 => "subsection",
+                    3 => "subsubsection",
+                    4 => "paragraph",
+                    _ => "subparagraph",
+                };
+                out.push_str(&format!("\\{}{{", command));
+                self.write_children(node, out);
+                out.push('}');
+                // `{#id}` 作为交叉引用的标签
+                if let Some(id) = &node.attrs.id {
+                    out.push_str(&format!("\\label{{{}}}", id));
+                }
+                out.push_str("\n\n");
+            }
+            NodeKind::ThematicBreak => out.push_str("\\noindent\\rule{\\linewidth}{0.4pt}\n\n"),
+            NodeKind::BlockQuote => {
+                out.push_str("\\begin{quote}\n");
+                self.write_children(node, out);
+                out.push_str("\\end{quote}\n\n");
+            }
+            NodeKind::Admonition { kind, title } => {
+                out.push_str("\\begin{quote}\n");
+                out.push_str(&format!(
+                    "\\textbf{{{}}}\n\n",
+                    escape_latex(&admonition_title(kind, title))
+                ));
+                self.write_children(node, out);
+                out.push_str("\\end{quote}\n\n");
+            }
+            NodeKind::List { start, .. } => {
+                let env = if start.is_some() {
+                    "enumerate"
+                } else {
+                    "itemize"
+                };
+                out.push_str(&format!("\\begin{{{}}}\n", env));
+                if let Some(start) = start.filter(|s| *s != 1) {
+                    out.push_str(&format!("\\setcounter{{enumi}}{{{}}}\n", start - 1));
+                }
+                self.write_children(node, out);
+                out.push_str(&format!("\\end{{{}}}\n\n", env));
+            }
+            NodeKind::ListItem => {
+                out.push_str("\\item ");
+                self.write_children(node, out);
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+            }
+            NodeKind::CodeBlock { literal, .. } => {
+                out.push_str("\\begin{verbatim}\n");
+                out.push_str(literal);
+                out.push_str("\\end{verbatim}\n\n");
+            }
+
+            NodeKind::Text { value } => out.push_str(&escape_latex(value)),
+            NodeKind::Code { literal } => {
+                out.push_str(&format!("\\texttt{{{}}}", escape_latex(literal)))
+            }
+            // 公式原样输出
+            NodeKind::Math { literal, display } => {
+                if *display {
+                    out.push_str(&format!("\\[{}\\]", literal));
+                } else {
+                    out.push_str(&format!("${}$", literal));
+                }
+            }
+            NodeKind::Entity { value, .. } => out.push_str(&escape_latex(value)),
+            NodeKind::HardBreak => out.push_str("\\\\\n"),
+            NodeKind::SoftBreak => out.push('\n'),
+            NodeKind::Emphasis => {
+                out.push_str("\\emph{");
+                self.write_children(node, out);
+                out.push('}');
+            }
+            NodeKind::Strong => {
+                out.push_str("\\textbf{");
+                self.write_children(node, out);
+                out.push('}');
+            }
+            NodeKind::Mark
+            | NodeKind::Insert
+            | NodeKind::Superscript
+            | NodeKind::Subscript
+            | NodeKind::Strikethrough => {
+                let command = match node.kind {
+                    NodeKind::Mark => "hl",
+                    NodeKind::Insert => "uline",
+                    NodeKind::Superscript => "textsuperscript",
+                    NodeKind::Subscript => "textsubscript",
+                    _ => "sout",
+                };
+                out.push_str(&format!("\\{}{{", command));
+                self.write_children(node, out);
+                out.push('}');
+            }
+            NodeKind::Link { destination, .. } => {
+                out.push_str(&format!("\\href{{{}}}{{", escape_url(destination)));
+                self.write_children(node, out);
+                out.push('}');
+            }
+            NodeKind::Image { destination, .. } => {
+                out.push_str(&format!("\\includegraphics{{{}}}", escape_url(destination)))
+            }
+            NodeKind::WikiLink {
+                destination: Some(destination),
+                embed: true,
+                ..
+            } => out.push_str(&format!("\\includegraphics{{{}}}", escape_url(destination))),
+            NodeKind::WikiLink {
+                destination: Some(destination),
+                ..
+            } => {
+                out.push_str(&format!("\\href{{{}}}{{", escape_url(destination)));
+                self.write_children(node, out);
+                out.push('}');
+            }
+            NodeKind::WikiLink { .. } => self.write_children(node, out),
+            NodeKind::HtmlInline { .. } => {}
+            NodeKind::Abbr { .. } => self.write_children(node, out),
+        }
+    }
+}
+
+/// 转义 LaTeX 特殊字符
+pub fn escape_latex(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    for c in src.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `\href` 中只需转义会破坏参数的字符
+fn escape_url(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    for c in src.chars() {
+        if matches!(c, '\\' | '#' | '%' | '{' | '}') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::syntax::parse;
+
+    use super::*;
+
+    fn to_latex(src: &str) -> String {
+        LatexRenderer::new().render(&parse(src))
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            to_latex("# A & B\n\n*em* **st** `a_b` [l](http://a.b/#x)\n\n2. x\n3. y\n"),
+            "\\section{A \\& B}\n\n\\emph{em} \\textbf{st} \\texttt{a\\_b} \
+             \\href{http://a.b/\\#x}{l}\n\n\
+             \\begin{enumerate}\n\\setcounter{enumi}{1}\n\\item x\n\n\\item y\n\n\\end{enumerate}\n\n"
+        );
+    }
+
+    #[test]
+    fn test_label() {
+        assert_eq!(
+            to_latex("## Intro {#sec:intro .x}"),
+            "\\subsection{Intro}\\label{sec:intro}\n\n"
+        );
+    }
+
+    #[test]
+    fn test_math() {
+        assert_eq!(
+            to_latex("price $5, $x_1^2$ and\n$$\\frac{a}{b}$$"),
+            "price \\$5, $x_1^2$ and\n\\[\\frac{a}{b}\\]\n\n"
+        );
+    }
+}
@@ -0,0 +1,589 @@
+use crate::syntax::{html, may_start_block, Attributes, Node, NodeKind};
+
+/// 将文档树输出为规范化的 markdown
+///
+/// 强调统一使用 `*`，无序列表使用原有标记，代码块统一使用围栏
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownRenderer;
+
+impl MarkdownRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, node: &Node) -> String {
+        let mut out = self.block(node);
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+
+    /// 块之间以空行分隔，紧凑列表项内的块只换行
+    fn blocks(&self, nodes: &[Node], tight: bool) -> String {
+        let sep = if tight { "\n" } else { "\n\n" };
+        nodes
+            .iter()
+            .map(|node| self.block(node))
+            .collect::<Vec<_>>()
+            .join(sep)
+    }
+
+    /// 输出块，结尾不含换行
+    fn block(&self, node: &Node) -> String {
+        let mut out = match &node.kind {
+            NodeKind::Document => return self.blocks(&node.children, false),
+            NodeKind::FrontMatter { format, raw } => {
+                format!("{}\n{}{}", format.fence(), raw, format.fence())
+            }
+            NodeKind::Paragraph => {
+                let mut starts = Vec::new();
+                line_starts(&node.children, &mut starts);
+                escape_line_start(&self.inlines(&node.children), &starts)
+            }
+            NodeKind::Heading { level } => {
+                let mut out = format!(
+                    "{} {}",
+                    "#".repeat(*level as usize),
+                    self.inlines(&node.children)
+                );
+                if !node.attrs.is_empty() {
+                    out.push(' ');
+                    out.push_str(&attributes(&node.attrs));
+                }
+                return out;
+            }
+            NodeKind::ThematicBreak => String::from("***"),
+            NodeKind::BlockQuote => prefix_lines(&self.blocks(&node.children, false), "> ", ">"),
+            NodeKind::Admonition { kind, title } => {
+                let open = if title.is_empty() {
+                    format!("::: {}", kind)
+                } else {
+                    format!("::: {} {}", kind, title)
+                };
+                let inner = self.blocks(&node.children, false);
+                if inner.is_empty() {
+                    format!("{}\n:::", open)
+                } else {
+                    format!("{}\n{}\n:::", open, inner)
+                }
+            }
+            NodeKind::List {
+                marker,
+                start,
+                tight,
+            } => {
+                let items = node.children.iter().enumerate().map(|(idx, item)| {
+                    let bullet = match start {
+                        Some(start) => format!("{}{} ", start + idx as u64, marker),
+                        None => format!("{} ", marker),
+                    };
+                    let content = self.blocks(&item.children, *tight);
+                    let indent = " ".repeat(bullet.len());
+                    let body = prefix_lines(&content, &indent, "");
+                    // 内容以空白开始时从下一行开始，保留空白；
+                    // 与无序列表标记连成分隔线时同样从下一行开始
+                    let first = content.split('\n').next().unwrap_or_default();
+                    let rule = start.is_none()
+                        && !first.is_empty()
+                        && first.chars().all(|c| c == *marker || c == ' ');
+                    if rule || content.starts_with([' ', '\t']) {
+                        format!("{}\n{}", bullet.trim_end(), body)
+                    } else {
+                        format!("{}{}", bullet, &body[indent.len().min(body.len())..])
+                    }
+                });
+                let sep = if *tight { "\n" } else { "\n\n" };
+                items.collect::<Vec<_>>().join(sep)
+            }
+            NodeKind::ListItem => self.blocks(&node.children, false),
+            NodeKind::CodeBlock { info, literal } => {
+                // 信息字符串含反引号时使用 `~` 围栏
+                let ch = if info.contains('`') { '~' } else { '`' };
+                let fence = ch.to_string().repeat(longest_run(literal, ch).max(2) + 1);
+                let mut info = info.clone();
+                if !node.attrs.is_empty() {
+                    if !info.is_empty() {
+                        info.push(' ');
+                    }
+                    info.push_str(&attributes(&node.attrs));
+                }
+                if info.starts_with(ch) {
+                    info.insert(0, ' ');
+                }
+                return format!("{}{}\n{}{}", fence, info, literal, fence);
+            }
+            // 未结束的 html 块延续到文档末尾，可能包含结尾的空行
+            NodeKind::HtmlBlock { literal } => {
+                literal.strip_suffix('\n').unwrap_or(literal).to_string()
+            }
+            NodeKind::Abbreviation { abbr, title } => format!("*[{}]: {}", abbr, title),
+            _ => self.inline(node, Some('*')),
+        };
+        // 其他块的属性单独一行
+        if !node.attrs.is_empty() {
+            out.push('\n');
+            out.push_str(&attributes(&node.attrs));
+        }
+        out
+    }
+
+    fn inlines(&self, nodes: &[Node]) -> String {
+        self.inlines_in(nodes, None)
+    }
+
+    /// `outer` 为外层强调的 `mark`，见 [`Self::inline`]
+    fn inlines_in(&self, nodes: &[Node], outer: Option<char>) -> String {
+        let mut out = String::new();
+        let mut empty_code = false;
+        let mut prev_text = false;
+        for (idx, node) in nodes.iter().enumerate() {
+            // 嵌套的强调交替使用 `*` 与 `_`，避免与外层的分隔符混淆；
+            // 单词内只能使用 `*`，内层也不再交替，与外层连成一个分隔符序列
+            let intraword = out.ends_with(char::is_alphanumeric)
+                || nodes.get(idx + 1).is_some_and(|n| {
+                    matches!(&n.kind, NodeKind::Text { value } if value.starts_with(char::is_alphanumeric))
+                });
+            // 相邻的强调同样交替使用
+            let mark = if intraword {
+                None
+            } else {
+                let (mark, other) = if outer == Some('*') {
+                    ('_', '*')
+                } else {
+                    ('*', '_')
+                };
+                Some(if out.ends_with(mark) { other } else { mark })
+            };
+            let mut text = self.inline(node, mark);
+            // 相邻的 `=` `+` 会与分隔符连成更长的序列，转义文本一侧的字符
+            if let Some('=' | '+') = out.chars().last().filter(|c| text.starts_with(*c)) {
+                if matches!(node.kind, NodeKind::Text { .. }) {
+                    text.insert(0, '\\');
+                } else if prev_text {
+                    out.insert(out.len() - 1, '\\');
+                }
+            }
+            prev_text = node.attrs.is_empty() && matches!(node.kind, NodeKind::Text { .. });
+            // 空的行内代码之后紧跟反引号时会连成一个序列，改用六个反引号
+            if empty_code && text.starts_with('`') {
+                out.push_str("````");
+            }
+            empty_code = node.attrs.is_empty()
+                && matches!(&node.kind, NodeKind::Code { literal } if literal.is_empty());
+            out.push_str(&text);
+        }
+        out
+    }
+
+    /// `mark` 为强调使用的分隔符，`None` 表示在单词内
+    fn inline(&self, node: &Node, mark: Option<char>) -> String {
+        let wrap = |mark: &str| format!("{}{}{}", mark, self.inlines(&node.children), mark);
+        let emphasis = |n: usize| {
+            let delim = mark.unwrap_or('*').to_string().repeat(n);
+            let inner = self.inlines_in(&node.children, mark);
+            format!("{}{}{}", delim, inner, delim)
+        };
+        let out = match &node.kind {
+            NodeKind::Text { value } => escape(value),
+            // 行内代码只有单个与三个反引号两种形式，内容不去除空格
+            NodeKind::Code { literal } => {
+                if literal.is_empty() {
+                    String::from("``")
+                } else if literal.contains('`') || literal.starts_with('\n') {
+                    format!("```{}```", literal)
+                } else {
+                    format!("`{}`", literal)
+                }
+            }
+            NodeKind::Math { literal, display } => {
+                let fence = if *display { "$$" } else { "$" };
+                format!("{}{}{}", fence, literal, fence)
+            }
+            NodeKind::Entity { literal, .. } => literal.clone(),
+            NodeKind::HardBreak => String::from("\\\n"),
+            NodeKind::SoftBreak => String::from("\n"),
+            NodeKind::Emphasis => emphasis(1),
+            NodeKind::Strong => emphasis(2),
+            NodeKind::Mark => wrap("=="),
+            NodeKind::Insert => wrap("++"),
+            NodeKind::Superscript => wrap("^"),
+            NodeKind::Subscript => wrap("~"),
+            NodeKind::Strikethrough => wrap("~~"),
+            NodeKind::Link { destination, title } => {
+                // 自动链接，文本必须是单个可以识别为自动链接的文本
+                if let [Node {
+                    kind: NodeKind::Text { value: text },
+                    ..
+                }] = node.children.as_slice()
+                {
+                    let uri = text == destination && html::is_uri_autolink(text);
+                    let email = destination.strip_prefix("mailto:") == Some(text.as_str())
+                        && html::is_email_autolink(text);
+                    if title.is_empty() && node.attrs.is_empty() && (uri || email) {
+                        return format!("<{}>", text);
+                    }
+                }
+                format!(
+                    "[{}]{}",
+                    self.inlines(&node.children),
+                    link_tail(destination, title)
+                )
+            }
+            NodeKind::Image { destination, title } => format!(
+                "![{}]{}",
+                self.inlines(&node.children),
+                link_tail(destination, title)
+            ),
+            NodeKind::HtmlInline { literal } => literal.clone(),
+            NodeKind::WikiLink {
+                target,
+                fragment,
+                embed,
+                ..
+            } => {
+                let mut link = target.clone();
+                if !fragment.is_empty() {
+                    link = format!("{}#{}", link, fragment);
+                }
+                let label = node.text_content();
+                if label != link {
+                    link = format!("{}|{}", link, label);
+                }
+                format!("{}[[{}]]", if *embed { "!" } else { "" }, link)
+            }
+            NodeKind::Abbr { .. } => self.inlines(&node.children),
+            _ => self.blocks(&node.children, false),
+        };
+        if node.attrs.is_empty() {
+            out
+        } else {
+            out + &attributes(&node.attrs)
+        }
+    }
+}
+
+/// `(destination "title")`，目标为空或含空白时使用尖括号
+fn link_tail(destination: &str, title: &str) -> String {
+    let destination = if destination.is_empty() || destination.contains(char::is_whitespace) {
+        format!(
+            "<{}>",
+            escape_chars(destination, &['\\', '<', '>', '[', ']', '`', '$'])
+        )
+    } else {
+        escape_chars(destination, &['\\', '(', ')', '<', '>', '[', ']', '`', '$'])
+    };
+    if title.is_empty() {
+        format!("({})", destination)
+    } else {
+        format!(
+            "({} \"{}\")",
+            destination,
+            escape_chars(title, &['\\', '"'])
+        )
+    }
+}
+
+/// 在 `chars` 中的字符之前添加反斜杠
+fn escape_chars(text: &str, chars: &[char]) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if chars.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn attributes(attrs: &Attributes) -> String {
+    let mut items = Vec::new();
+    if let Some(id) = &attrs.id {
+        items.push(format!("#{}", id));
+    }
+    items.extend(attrs.classes.iter().map(|c| format!(".{}", c)));
+    for (key, value) in &attrs.pairs {
+        if value.is_empty() || value.contains(char::is_whitespace) {
+            items.push(format!("{}=\"{}\"", key, value));
+        } else {
+            items.push(format!("{}={}", key, value));
+        }
+    }
+    format!("{{{}}}", items.join(" "))
+}
+
+/// 为每一行添加前缀，空行使用 `blank`
+fn prefix_lines(text: &str, prefix: &str, blank: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                blank.to_string()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn longest_run(text: &str, ch: char) -> usize {
+    text.split(|c| c != ch).map(str::len).max().unwrap_or(0)
+}
+
+/// 转义行内的特殊字符
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut prev = None;
+    while let Some(c) = chars.next() {
+        let special = matches!(c, '\\' | '*' | '_' | '[' | ']' | '`' | '<' | '$' | '~' | '^')
+            // 连续的 `=` `+` 可能构成分隔符
+            || (matches!(c, '=' | '+') && (prev == Some(c) || chars.peek() == Some(&c)))
+            // 可能被识别为字符引用的 `&`
+            || (c == '&' && chars.peek().is_some_and(|n| n.is_ascii_alphanumeric() || *n == '#'));
+        if special {
+            out.push('\\');
+        }
+        out.push(c);
+        prev = Some(c);
+    }
+    out
+}
+
+/// 段落中换行之后的内容
+#[derive(Clone, Copy)]
+enum LineStart {
+    // 以文本开始，可以转义行首字符
+    Text,
+    // 以其他行内节点开始，只能通过缩进避免识别为块
+    Node,
+    // 原样输出的代码等内容中的换行，同样只能缩进
+    Literal,
+}
+
+/// 按顺序收集段落中每个换行之后的内容
+fn line_starts(nodes: &[Node], starts: &mut Vec<LineStart>) {
+    for (idx, node) in nodes.iter().enumerate() {
+        match &node.kind {
+            NodeKind::SoftBreak | NodeKind::HardBreak => {
+                let text = nodes
+                    .get(idx + 1)
+                    .is_some_and(|n| matches!(n.kind, NodeKind::Text { .. }));
+                starts.push(if text {
+                    LineStart::Text
+                } else {
+                    LineStart::Node
+                });
+            }
+            NodeKind::Code { literal }
+            | NodeKind::Math { literal, .. }
+            | NodeKind::HtmlInline { literal } => {
+                starts.extend(literal.matches('\n').map(|_| LineStart::Literal));
+            }
+            NodeKind::Link { title, .. } | NodeKind::Image { title, .. } => {
+                line_starts(&node.children, starts);
+                starts.extend(title.matches('\n').map(|_| LineStart::Literal));
+            }
+            _ => line_starts(&node.children, starts),
+        }
+    }
+}
+
+/// 转义段落中会被识别为块开始的行，`starts` 见 [`line_starts`]
+///
+/// 段落的后续行可以任意缩进，不以文本开始的行缩进四列
+fn escape_line_start(text: &str, starts: &[LineStart]) -> String {
+    text.split('\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            let start = match idx.checked_sub(1).and_then(|i| starts.get(i)) {
+                Some(start @ (LineStart::Literal | LineStart::Node)) => *start,
+                _ => LineStart::Text,
+            };
+            if !may_start_block(line) {
+                return line.to_string();
+            }
+            if !matches!(start, LineStart::Text) {
+                return format!("    {}", line);
+            }
+            // 有序列表转义分隔符，其余转义行首字符
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            match line[digits..].chars().next() {
+                Some(d @ ('.' | ')')) if digits > 0 => {
+                    format!("{}\\{}{}", &line[..digits], d, &line[digits + 1..])
+                }
+                _ => format!("\\{}", line),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::syntax::{parse, parse_with, Options};
+
+    use super::*;
+
+    fn fmt(src: &str) -> String {
+        MarkdownRenderer::new().render(&parse(src))
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            fmt("#  Title  {#t}\n__strong__ _em_ `a` [l]( /u  'x' ) <https://a.b>\n* a\n* b\n\n\n1) x\n\n   y\n> q\nlazy\n```\ncode\n```"),
+            "# Title {#t}\n\n**strong** *em* `a` [l](/u \"x\") <https://a.b>\n\n\
+             * a\n* b\n\n1) x\n\n   y\n\n> q\n> lazy\n\n```\ncode\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let src = "---\ntitle: x\n---\n# A *b* \\# c\n\n- 1\\. not list\n  - nested `` ` ``\n\
+                   \n2. two\n3. three\n\n> [!NOTE]\n> body\n\n![i](<a b.png> \"t\")\\\nnext &copy; $x$ [[W|w]]\n\n\
+                   ***\n\n<div>\nraw\n</div>\n";
+        let doc = parse(src);
+        let formatted = MarkdownRenderer::new().render(&doc);
+        assert_eq!(parse(&formatted), doc);
+        // 格式化是幂等的
+        assert_eq!(
+            MarkdownRenderer::new().render(&parse(&formatted)),
+            formatted
+        );
+
+        // 可选语法开启时行首的 `=` `+` 不应被转义
+        let opts = Options::new()
+            .mark(true)
+            .insert(true)
+            .superscript(true)
+            .subscript(true)
+            .strikethrough(true)
+            .abbreviations(true);
+        let src = "==m== ++i++ ^s^ ~b~ ~~d~~\n=x\n+y\n-z\n#w\n|t|\n\\- a\n1\\. b\n\\# c\n\\> d\n\\:::note\n\\{.x}\n";
+        let doc = parse_with(src, &opts);
+        let formatted = MarkdownRenderer::new().render(&doc);
+        assert_eq!(parse_with(&formatted, &opts), doc);
+        assert!(formatted.starts_with("==m== ++i++ ^s^ ~b~ ~~d~~\n=x\n+y\n-z\n#w\n|t|\n"));
+    }
+
+    fn extended() -> Options {
+        Options::new()
+            .mark(true)
+            .insert(true)
+            .superscript(true)
+            .subscript(true)
+            .strikethrough(true)
+            .abbreviations(true)
+    }
+
+    /// 格式化后重新解析得到相同的语法树，并且格式化是幂等的
+    fn assert_round_trip(src: &str, opts: &Options) {
+        let doc = parse_with(src, opts);
+        let formatted = MarkdownRenderer::new().render(&doc);
+        assert_eq!(
+            parse_with(&formatted, opts),
+            doc,
+            "{:?} => {:?}",
+            src,
+            formatted
+        );
+        assert_eq!(
+            MarkdownRenderer::new().render(&parse_with(&formatted, opts)),
+            formatted,
+            "{:?}",
+            src
+        );
+    }
+
+    #[test]
+    fn test_round_trip_cases() {
+        let cases = [
+            "http://`b]({#> @<!--\n---<!--):::",
+            "> ]&#```\n`![http://-->1)",
+            "[[中```{{**::: note*`&http://#a[!NOTE]```:::]]",
+            "a\n```\nb `` ``` `x\n    ```` c",
+            "* ***\n- * *\n",
+            "x~ ~~d~~~ a==b ==m===c ++i++",
+            "<.x***中|![!NOTE]- \"]*---~**",
+            "_a_*b* **c**__d__",
+            "[a](<b c> \"t\") [x](](<!]$)--> [y](\'[[)",
+            "a <!--\nb",
+        ];
+        for src in cases {
+            assert_round_trip(src, &Options::new());
+            assert_round_trip(src, &extended());
+        }
+    }
+
+    #[test]
+    fn test_round_trip_random() {
+        const PIECES: &[&str] = &[
+            "http://",
+            "`",
+            "```",
+            "[",
+            "]",
+            "(",
+            ")",
+            "](",
+            "](<",
+            "\"",
+            "'",
+            "{",
+            "}",
+            "#",
+            "> ",
+            "@",
+            "<!--",
+            "-->",
+            "---",
+            ":::",
+            "::: note",
+            "[!NOTE]",
+            "**",
+            "*",
+            "&",
+            "!",
+            "\n",
+            "中",
+            "1)",
+            "a",
+            " ",
+            "[[",
+            "]]",
+            "_",
+            "$",
+            "<",
+            "- ",
+            "1. ",
+            "~~~",
+            "    ",
+            "\\",
+            "|",
+            "#a",
+            ".x",
+            "==",
+            "++",
+            "^",
+            "~",
+            "~~",
+            "*[a]: b\n",
+        ];
+        // 固定种子的线性同余生成器，保证结果可以复现
+        let mut seed: u64 = 1;
+        let mut next = |n: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % n
+        };
+        for _ in 0..1000 {
+            let src = (0..next(16))
+                .map(|_| PIECES[next(PIECES.len())])
+                .collect::<String>();
+            assert_round_trip(&src, &Options::new());
+            assert_round_trip(&src, &extended());
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! 将文档树输出为其他格式
+//!
+pub mod html;
+pub mod latex;
+pub mod markdown;
+pub mod safe;
+
+/// 提示块显示的标题，未给出标题时使用首字母大写的类型
+pub(crate) fn admonition_title(kind: &str, title: &str) -> String {
+    if !title.is_empty() {
+        return title.to_string();
+    }
+    let mut chars = kind.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,453 @@
+//! 安全输出模式
+//!
+//! 用于输出用户提交的内容：处理原始 html，过滤危险的链接协议
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    lexical::tokens::entity,
+    syntax::html::{parse_tag, HtmlTag},
+};
+
+use super::html::escape_html;
+
+/// 原始 html 的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawHtml {
+    /// 全部转义为文本
+    Escape,
+    /// 只保留白名单内的标签与属性，其余转义
+    Allowlist,
+}
+
+/// 安全模式配置
+#[derive(Debug, Clone)]
+pub struct SafeMode {
+    raw_html: RawHtml,
+    // 标签 -> 允许的属性
+    tags: HashMap<String, HashSet<String>>,
+    // 所有标签都允许的属性
+    global_attrs: HashSet<String>,
+    // 允许的链接协议，相对链接总是允许
+    schemes: HashSet<String>,
+    // 外部链接添加的 rel
+    rel: Option<String>,
+}
+
+const DEFAULT_TAGS: [(&str, &[&str]); 45] = [
+    ("a", &["href"]),
+    ("abbr", &[]),
+    ("b", &[]),
+    ("blockquote", &["cite"]),
+    ("br", &[]),
+    ("code", &[]),
+    ("dd", &[]),
+    ("del", &[]),
+    ("details", &["open"]),
+    ("div", &[]),
+    ("dl", &[]),
+    ("dt", &[]),
+    ("em", &[]),
+    ("h1", &[]),
+    ("h2", &[]),
+    ("h3", &[]),
+    ("h4", &[]),
+    ("h5", &[]),
+    ("h6", &[]),
+    ("hr", &[]),
+    ("i", &[]),
+    ("img", &["src", "alt", "width", "height"]),
+    ("ins", &[]),
+    ("kbd", &[]),
+    ("li", &[]),
+    ("mark", &[]),
+    ("ol", &["start"]),
+    ("p", &[]),
+    ("pre", &[]),
+    ("q", &["cite"]),
+    ("s", &[]),
+    ("samp", &[]),
+    ("span", &[]),
+    ("strong", &[]),
+    ("sub", &[]),
+    ("summary", &[]),
+    ("sup", &[]),
+    ("table", &[]),
+    ("tbody", &[]),
+    ("td", &["align", "colspan", "rowspan"]),
+    ("th", &["align", "colspan", "rowspan"]),
+    ("thead", &[]),
+    ("tr", &[]),
+    ("u", &[]),
+    ("ul", &[]),
+];
+
+// 值为链接的属性
+const URL_ATTRS: [&str; 3] = ["href", "src", "cite"];
+
+impl Default for SafeMode {
+    fn default() -> Self {
+        Self {
+            raw_html: RawHtml::Escape,
+            tags: DEFAULT_TAGS
+                .iter()
+                .map(|(tag, attrs)| {
+                    (
+                        tag.to_string(),
+                        attrs.iter().map(|a| a.to_string()).collect(),
+                    )
+                })
+                .collect(),
+            global_attrs: ["title", "lang", "dir"]
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+            schemes: ["http", "https", "mailto"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            rel: Some(String::from("nofollow noopener")),
+        }
+    }
+}
+
+impl SafeMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn raw_html(mut self, raw_html: RawHtml) -> Self {
+        self.raw_html = raw_html;
+        self
+    }
+
+    /// 允许标签及其属性
+    pub fn allow_tag(mut self, tag: &str, attrs: &[&str]) -> Self {
+        self.tags
+            .entry(tag.to_ascii_lowercase())
+            .or_default()
+            .extend(attrs.iter().map(|a| a.to_ascii_lowercase()));
+        self
+    }
+
+    /// 禁止标签
+    pub fn deny_tag(mut self, tag: &str) -> Self {
+        self.tags.remove(&tag.to_ascii_lowercase());
+        self
+    }
+
+    pub fn allow_scheme(mut self, scheme: &str) -> Self {
+        self.schemes.insert(scheme.to_ascii_lowercase());
+        self
+    }
+
+    pub fn deny_scheme(mut self, scheme: &str) -> Self {
+        self.schemes.remove(&scheme.to_ascii_lowercase());
+        self
+    }
+
+    /// 外部链接的 rel 属性，`None` 表示不添加
+    pub fn rel(mut self, rel: Option<&str>) -> Self {
+        self.rel = rel.map(String::from);
+        self
+    }
+
+    /// 链接是否可以输出
+    pub fn is_url_allowed(&self, url: &str) -> bool {
+        match url_scheme(url) {
+            Some(scheme) => self.schemes.contains(&scheme),
+            None => true,
+        }
+    }
+
+    /// 属性块中的属性能否输出，链接属性总是不允许
+    pub fn is_attr_allowed(&self, tag: &str, name: &str) -> bool {
+        !URL_ATTRS.contains(&name)
+            && (self.global_attrs.contains(name)
+                || self.tags.get(tag).is_some_and(|attrs| attrs.contains(name)))
+    }
+
+    /// 链接需要添加的 rel
+    pub fn rel_for(&self, url: &str) -> Option<&str> {
+        self.rel.as_deref().filter(|_| url_scheme(url).is_some())
+    }
+
+    /// 处理原始 html 片段
+    pub fn sanitize(&self, literal: &str) -> String {
+        if self.raw_html == RawHtml::Escape {
+            return escape_html(literal);
+        }
+        let mut out = String::with_capacity(literal.len());
+        let mut rest = literal;
+        while let Some(c) = rest.chars().next() {
+            if c == '<' {
+                if let Some(len) = skip_markup(rest) {
+                    rest = &rest[len..];
+                    continue;
+                }
+                if let Some((tag, len)) = parse_tag(rest) {
+                    match self.write_tag(&tag) {
+                        Some(t) => out.push_str(&t),
+                        None => out.push_str(&escape_html(&rest[..len])),
+                    }
+                    rest = &rest[len..];
+                    continue;
+                }
+                out.push_str("&lt;");
+            } else if c == '&' {
+                // 保留实体引用
+                if is_entity(rest) {
+                    out.push('&');
+                } else {
+                    out.push_str("&amp;");
+                }
+            } else if c == '>' {
+                out.push_str("&gt;");
+            } else {
+                out.push(c);
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+        out
+    }
+
+    /// 重新输出白名单内的标签，不在白名单内返回 `None`
+    fn write_tag(&self, tag: &HtmlTag) -> Option<String> {
+        let allowed = self.tags.get(&tag.name)?;
+        if tag.closing {
+            return Some(format!("</{}>", tag.name));
+        }
+        let mut out = format!("<{}", tag.name);
+        let mut rel = None;
+        for (name, value) in &tag.attrs {
+            if !(allowed.contains(name) || self.global_attrs.contains(name)) {
+                continue;
+            }
+            // 属性值中的字符引用只解码一次，协议检查与转义都针对解码后的值
+            let value = entity::decode_all(value.as_deref().unwrap_or_default());
+            if URL_ATTRS.contains(&name.as_str()) {
+                if !self.is_url_allowed(&value) {
+                    continue;
+                }
+                if tag.name == "a" && name == "href" {
+                    rel = self.rel_for(&value);
+                }
+            }
+            out.push_str(&format!(" {}=\"{}\"", name, escape_html(&value)));
+        }
+        if let Some(rel) = rel {
+            out.push_str(&format!(" rel=\"{}\"", rel));
+        }
+        out.push_str(if tag.self_closing { " />" } else { ">" });
+        Some(out)
+    }
+}
+
+/// 注释、声明、处理指令等直接丢弃，返回其长度
+fn skip_markup(src: &str) -> Option<usize> {
+    let end = if src.starts_with("<!--") {
+        src.find("-->").map(|p| p + 3)
+    } else if src.starts_with("<![CDATA[") {
+        src.find("]]>").map(|p| p + 3)
+    } else if src.starts_with("<?") {
+        src.find("?>").map(|p| p + 2)
+    } else if src.starts_with("<!") {
+        src.find('>').map(|p| p + 1)
+    } else {
+        return None;
+    };
+    Some(end.unwrap_or(src.len()))
+}
+
+fn is_entity(src: &str) -> bool {
+    let body = match src[1..].find(';') {
+        Some(end) => &src[1..end + 1],
+        None => return false,
+    };
+    let name = body.strip_prefix('#').unwrap_or(body);
+    !name.is_empty() && name.len() <= 32 && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// 提取链接协议（小写），相对链接返回 `None`
+///
+/// 浏览器会忽略协议中的空白与控制字符，并且会解码数字字符引用，
+/// 因此先做同样的处理再判断
+pub fn url_scheme(url: &str) -> Option<String> {
+    let mut cleaned = String::with_capacity(url.len());
+    let mut rest = url;
+    while let Some(c) = rest.chars().next() {
+        if c == '&' {
+            if let Some((decoded, len)) = numeric_reference(rest) {
+                if !decoded.is_whitespace() && !decoded.is_control() {
+                    cleaned.push(decoded);
+                }
+                rest = &rest[len..];
+                continue;
+            }
+        }
+        if !c.is_whitespace() && !c.is_control() {
+            cleaned.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    let end = cleaned.find([':', '/', '?', '#'])?;
+    if &cleaned[end..end + 1] != ":" || end == 0 {
+        return None;
+    }
+    Some(cleaned[..end].to_ascii_lowercase())
+}
+
+/// `&#123;` `&#x7B;` 形式的数字字符引用，分号可以省略
+fn numeric_reference(src: &str) -> Option<(char, usize)> {
+    let body = src.strip_prefix("&#")?;
+    let (radix, digits) = match body.strip_prefix(['x', 'X']) {
+        Some(hex) => (16, hex),
+        None => (10, body),
+    };
+    let len = digits.chars().take_while(|c| c.is_digit(radix)).count();
+    if len == 0 {
+        return None;
+    }
+    let value = u32::from_str_radix(&digits[..len], radix).ok()?;
+    let mut used = src.len() - digits.len() + len;
+    if src[used..].starts_with(';') {
+        used += 1;
+    }
+    Some((char::from_u32(value).unwrap_or('\u{FFFD}'), used))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{render::html::HtmlRenderer, syntax::parse};
+
+    use super::*;
+
+    /// 常见的 xss 载荷
+    const XSS: [&str; 24] = [
+        "<script>alert(1)</script>",
+        "<SCRIPT SRC=http://xss.example/xss.js></SCRIPT>",
+        "<img src=x onerror=alert(1)>",
+        "<img src=\"javascript:alert(1)\">",
+        "<IMG SRC=JaVaScRiPt:alert('XSS')>",
+        "<img src=\"jav&#x09;ascript:alert(1)\">",
+        "<img src=\"&#106;&#97;&#118;&#97;&#115;&#99;&#114;&#105;&#112;&#116;&#58;alert(1)\">",
+        "<a href=\"javascript:alert(1)\">x</a>",
+        "<a href=\" javascript:alert(1)\">x</a>",
+        "<a href=\"vbscript:msgbox(1)\">x</a>",
+        "<a href=\"data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==\">x</a>",
+        "<svg onload=alert(1)>",
+        "<body onload=alert(1)>",
+        "<iframe src=\"javascript:alert(1)\"></iframe>",
+        "<div style=\"background:url(javascript:alert(1))\">x</div>",
+        "<div onmouseover=\"alert(1)\">x</div>",
+        "<!--<script>alert(1)</script>-->",
+        "<math><mi xlink:href=\"javascript:alert(1)\">x</mi></math>",
+        "[x](javascript:alert(1))",
+        "[x](JAVASCRIPT:alert(1) \"t\")",
+        "![x](data:image/svg+xml;base64,PHN2Zz4=)",
+        "<javascript:alert(1)>",
+        "<vbscript:msgbox(1)>",
+        "text <span onclick=\"alert(1)\">x</span> <object data=x.swf></object>",
+    ];
+
+    /// 检查输出中真实存在的标签
+    fn assert_safe(html: &str, mode: &SafeMode) {
+        let mut rest = html;
+        while let Some(pos) = rest.find('<') {
+            rest = &rest[pos..];
+            let (tag, len) = parse_tag(rest).unwrap_or_else(|| panic!("bad markup in {:?}", html));
+            assert!(
+                mode.tags.contains_key(&tag.name),
+                "tag {:?} in {:?}",
+                tag.name,
+                html
+            );
+            for (name, value) in &tag.attrs {
+                assert!(!name.starts_with("on"), "attr {:?} in {:?}", name, html);
+                assert_ne!(name, "style");
+                if let Some(v) = value {
+                    assert!(mode.is_url_allowed(v), "url {:?} in {:?}", v, html);
+                }
+            }
+            rest = &rest[len..];
+        }
+    }
+
+    #[test]
+    fn test_xss_corpus() {
+        for raw_html in [RawHtml::Escape, RawHtml::Allowlist] {
+            let mode = SafeMode::new().raw_html(raw_html);
+            let renderer = HtmlRenderer::new().safe_mode(mode.clone());
+            for payload in XSS {
+                let html = renderer.render(&parse(payload));
+                assert_safe(&html, &mode);
+            }
+        }
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let mode = SafeMode::new().raw_html(RawHtml::Allowlist);
+        assert_eq!(
+            mode.sanitize("<a href=\"https://x.y\" onclick=\"f()\" title=t>a &amp; b</a>"),
+            "<a href=\"https://x.y\" title=\"t\" rel=\"nofollow noopener\">a &amp; b</a>"
+        );
+        assert_eq!(
+            mode.sanitize("<a href=\"/local\">x</a> <b>1 < 2</b>"),
+            "<a href=\"/local\">x</a> <b>1 &lt; 2</b>"
+        );
+        assert_eq!(
+            mode.sanitize("<script>alert(1)</script><!-- x -->"),
+            "&lt;script&gt;alert(1)&lt;/script&gt;"
+        );
+        let mode = mode
+            .allow_tag("video", &["src", "controls"])
+            .allow_scheme("data");
+        assert_eq!(
+            mode.sanitize("<video src=\"data:video/mp4,x\" controls autoplay>"),
+            "<video src=\"data:video/mp4,x\" controls=\"\">"
+        );
+
+        // 属性值先解码字符引用再转义
+        let mode = SafeMode::new().raw_html(RawHtml::Allowlist).rel(None);
+        assert_eq!(
+            mode.sanitize("<a href=\"?a=1&amp;b=2\" title=\"x &quot;y&quot;\">"),
+            "<a href=\"?a=1&amp;b=2\" title=\"x &quot;y&quot;\">"
+        );
+        assert_eq!(
+            mode.sanitize("<a href=\"jav&#x61;script:alert(1)\">"),
+            "<a>"
+        );
+    }
+
+    #[test]
+    fn test_url_scheme() {
+        assert_eq!(url_scheme("HTTPS://a.b"), Some(String::from("https")));
+        assert_eq!(
+            url_scheme("java\tscript:x"),
+            Some(String::from("javascript"))
+        );
+        assert_eq!(
+            url_scheme("&#x6A;avascript:x"),
+            Some(String::from("javascript"))
+        );
+        assert_eq!(url_scheme("/path:with:colon"), None);
+        assert_eq!(url_scheme("page#a:b"), None);
+        assert_eq!(url_scheme("./a.md"), None);
+    }
+
+    #[test]
+    fn test_markdown_links() {
+        let renderer = HtmlRenderer::new().safe_mode(SafeMode::new());
+        assert_eq!(
+            renderer.render(&parse("[a](https://x.y) [b](javascript:f()) [c](/d)")),
+            "<p><a href=\"https://x.y\" rel=\"nofollow noopener\">a</a> <a>b</a> \
+             <a href=\"/d\">c</a></p>\n"
+        );
+        assert_eq!(
+            renderer.render(&parse("<div onclick=\"f()\">x</div>")),
+            "&lt;div onclick=&quot;f()&quot;&gt;x&lt;/div&gt;\n"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! 缩写 `*[HTML]: Hyper Text Markup Language`
+//!
+//! 定义在整个文档内有效，文本中完整出现的缩写包裹为 `Abbr` 节点，
+//! 代码与链接内的文本不处理
+
+use std::collections::HashMap;
+
+use super::ast::{Node, NodeKind};
+
+/// 缩写定义行，返回缩写与标题
+pub(super) fn definition(content: &str) -> Option<(String, String)> {
+    let rest = content.strip_prefix("*[")?;
+    let (abbr, title) = rest.split_once("]:")?;
+    if abbr.trim().is_empty() || abbr.contains(']') {
+        return None;
+    }
+    Some((abbr.trim().to_string(), title.trim().to_string()))
+}
+
+/// 收集文档内的全部定义并替换文本中的缩写
+pub(super) fn apply(doc: &mut Node) {
+    let mut defs = HashMap::new();
+    collect(doc, &mut defs);
+    if defs.is_empty() {
+        return;
+    }
+    // 较长的缩写优先匹配
+    let mut defs = defs.into_iter().collect::<Vec<_>>();
+    defs.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+    replace(doc, &defs);
+}
+
+fn collect(node: &Node, defs: &mut HashMap<String, String>) {
+    if let NodeKind::Abbreviation { abbr, title } = &node.kind {
+        defs.insert(abbr.clone(), title.clone());
+    }
+    for child in &node.children {
+        collect(child, defs);
+    }
+}
+
+fn replace(node: &mut Node, defs: &[(String, String)]) {
+    let children = std::mem::take(&mut node.children);
+    for mut child in children {
+        match &child.kind {
+            NodeKind::Text { value } => node.children.extend(split(value, defs)),
+            NodeKind::Link { .. }
+            | NodeKind::Image { .. }
+            | NodeKind::WikiLink { .. }
+            | NodeKind::Abbr { .. } => node.children.push(child),
+            _ => {
+                replace(&mut child, defs);
+                node.children.push(child);
+            }
+        }
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// 按缩写拆分文本，缩写前后不能是字母数字
+fn split(value: &str, defs: &[(String, String)]) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut start = 0;
+    let mut prev = None;
+    let mut iter = value.char_indices();
+    while let Some((idx, c)) = iter.next() {
+        if prev.is_some_and(is_word) {
+            prev = Some(c);
+            continue;
+        }
+        let found = defs.iter().find(|(abbr, _)| {
+            value[idx..].starts_with(abbr.as_str())
+                && !value[idx + abbr.len()..].starts_with(is_word)
+        });
+        let (abbr, title) = match found {
+            Some(def) => def,
+            None => {
+                prev = Some(c);
+                continue;
+            }
+        };
+        if start < idx {
+            nodes.push(Node::text(&value[start..idx]));
+        }
+        nodes.push(Node::with_children(
+            NodeKind::Abbr {
+                title: title.clone(),
+            },
+            vec![Node::text(abbr.as_str())],
+        ));
+        start = idx + abbr.len();
+        // 跳过缩写剩余的字符
+        prev = abbr.chars().last();
+        for _ in 1..abbr.chars().count() {
+            iter.next();
+        }
+    }
+    if start < value.len() {
+        nodes.push(Node::text(&value[start..]));
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod test {
+    use crate::syntax::{parse_with, Options};
+
+    use super::*;
+
+    #[test]
+    fn test_definition() {
+        assert_eq!(
+            definition("*[W3C]:  World Wide Web Consortium "),
+            Some((
+                String::from("W3C"),
+                String::from("World Wide Web Consortium")
+            ))
+        );
+        assert_eq!(definition("*[]: x"), None);
+        assert_eq!(definition("*[a] b"), None);
+    }
+
+    #[test]
+    fn test_apply() {
+        let opts = Options::new().abbreviations(true);
+        let doc = parse_with(
+            "HTML and XHTML, `HTML` [HTML](/h) HTML5 HTML.\n\n*[HTML]: Hyper Text Markup Language",
+            &opts,
+        );
+        let para = &doc.children[0];
+        let abbr = |title: &str| NodeKind::Abbr {
+            title: String::from(title),
+        };
+        assert_eq!(para.children[0].kind, abbr("Hyper Text Markup Language"));
+        assert_eq!(para.children[0].text_content(), "HTML");
+        assert_eq!(para.children[1], Node::text(" and XHTML, "));
+        // 代码与链接内不替换
+        assert!(matches!(para.children[2].kind, NodeKind::Code { .. }));
+        assert_eq!(para.children[4].children, vec![Node::text("HTML")]);
+        assert_eq!(para.children[5], Node::text(" HTML5 "));
+        assert_eq!(para.children[6].kind, abbr("Hyper Text Markup Language"));
+        assert_eq!(para.children[7], Node::text("."));
+        assert!(matches!(
+            doc.children[1].kind,
+            NodeKind::Abbreviation { .. }
+        ));
+    }
+}
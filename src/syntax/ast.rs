@@ -0,0 +1,151 @@
+use super::{attributes::Attributes, front_matter::FrontMatterFormat};
+
+/// 文档树节点
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Node {
+    #[serde(flatten)]
+    pub kind: NodeKind,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Node>,
+    /// `{#id .class key=value}` 属性
+    #[serde(skip_serializing_if = "Attributes::is_empty")]
+    pub attrs: Attributes,
+}
+
+/// 节点类型
+#[derive(Debug, Clone, PartialEq, serde::Serialize, schemars::JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeKind {
+    // 块级节点
+    Document,
+    /// 文档开头的元数据，`raw` 不包含分隔线
+    FrontMatter {
+        format: FrontMatterFormat,
+        raw: String,
+    },
+    Paragraph,
+    Heading {
+        level: u8,
+    },
+    ThematicBreak,
+    /// 引用块，子节点为块级节点
+    BlockQuote,
+    /// 提示块，来自 `> [!NOTE]` 或 `::: note`，`kind` 为小写的类型，`title` 可以为空
+    Admonition {
+        kind: String,
+        title: String,
+    },
+    /// 列表，`marker` 为无序列表的 `-` `*` `+` 或有序列表的 `.` `)`
+    ///
+    /// 有序列表的 `start` 为首项序号，`tight` 为紧凑列表，输出时不包裹段落
+    List {
+        marker: char,
+        start: Option<u64>,
+        tight: bool,
+    },
+    ListItem,
+    CodeBlock {
+        info: String,
+        literal: String,
+    },
+    /// html 块，原样输出
+    HtmlBlock {
+        literal: String,
+    },
+    /// 缩写定义 `*[abbr]: title`，不输出
+    Abbreviation {
+        abbr: String,
+        title: String,
+    },
+
+    // 行内节点
+    Text {
+        value: String,
+    },
+    Code {
+        literal: String,
+    },
+    /// 数学公式，`display` 为 `$$…$$` 独立公式
+    Math {
+        literal: String,
+        display: bool,
+    },
+    /// 字符引用，保留原始写法
+    Entity {
+        literal: String,
+        value: String,
+    },
+    /// 行末两个空格或反斜杠形成的强制换行
+    HardBreak,
+    /// 段落内普通换行
+    SoftBreak,
+    Emphasis,
+    Strong,
+    /// 行内扩展 `==mark==` `++ins++` `^sup^` `~sub~` `~~strike~~`
+    Mark,
+    Insert,
+    Superscript,
+    Subscript,
+    Strikethrough,
+    Link {
+        destination: String,
+        title: String,
+    },
+    Image {
+        destination: String,
+        title: String,
+    },
+    /// 行内 html，原样输出
+    HtmlInline {
+        literal: String,
+    },
+    /// 文本中出现的缩写，子节点为缩写文本
+    Abbr {
+        title: String,
+    },
+    /// 维基链接 `[[target#fragment|label]]`，`embed` 为 `![[…]]` 嵌入
+    ///
+    /// `destination` 为解析得到的地址，目标不存在时为空
+    WikiLink {
+        target: String,
+        fragment: String,
+        destination: Option<String>,
+        embed: bool,
+    },
+}
+
+impl Node {
+    pub fn new(kind: NodeKind) -> Self {
+        Self::with_children(kind, vec![])
+    }
+
+    pub fn with_children(kind: NodeKind, children: Vec<Node>) -> Self {
+        Self {
+            kind,
+            children,
+            attrs: Attributes::default(),
+        }
+    }
+
+    pub fn with_attrs(mut self, attrs: Attributes) -> Self {
+        self.attrs = attrs;
+        self
+    }
+
+    pub fn text<S: Into<String>>(value: S) -> Self {
+        Self::new(NodeKind::Text {
+            value: value.into(),
+        })
+    }
+
+    /// 节点下全部文本内容
+    pub fn text_content(&self) -> String {
+        match &self.kind {
+            NodeKind::Text { value } => value.clone(),
+            NodeKind::Code { literal } | NodeKind::Math { literal, .. } => literal.clone(),
+            NodeKind::Entity { value, .. } => value.clone(),
+            NodeKind::HardBreak | NodeKind::SoftBreak => String::from("\n"),
+            _ => self.children.iter().map(Node::text_content).collect(),
+        }
+    }
+}
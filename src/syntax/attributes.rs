@@ -0,0 +1,141 @@
+//! 属性块 `{#id .class key=value}`
+//!
+//! 可以附加在标题、围栏代码块、链接、图片之后，或单独一行附加在前一个块上
+
+/// 节点的属性
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct Attributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<String>,
+    /// 其他 `key=value` 属性，保持书写顺序
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<(String, String)>,
+}
+
+impl Attributes {
+    pub fn is_empty(&self) -> bool {
+        self.id.is_none() && self.classes.is_empty() && self.pairs.is_empty()
+    }
+
+    /// 合并属性，`id` 以后者为准
+    pub fn extend(&mut self, other: Attributes) {
+        if other.id.is_some() {
+            self.id = other.id;
+        }
+        self.classes.extend(other.classes);
+        self.pairs.extend(other.pairs);
+    }
+
+    /// 解析包含花括号的属性块，允许 kramdown 的 `{: …}` 写法
+    ///
+    /// 存在无法识别的部分或内容为空时返回 `None`，此时花括号按普通文本处理
+    pub(crate) fn parse(src: &str) -> Option<Self> {
+        let inner = src.strip_prefix('{')?.strip_suffix('}')?;
+        let inner = inner.strip_prefix(':').unwrap_or(inner);
+        let mut attrs = Self::default();
+        let mut rest = inner.trim_start();
+        while !rest.is_empty() {
+            let (item, next) = split_item(rest)?;
+            if let Some(id) = item.strip_prefix('#') {
+                attrs.id = Some(name(id)?.to_string());
+            } else if let Some(class) = item.strip_prefix('.') {
+                attrs.classes.push(name(class)?.to_string());
+            } else {
+                let (key, value) = item.split_once('=')?;
+                let value = unquote(value);
+                match name(key)? {
+                    "id" => attrs.id = Some(value),
+                    "class" => attrs
+                        .classes
+                        .extend(value.split_whitespace().map(String::from)),
+                    key => attrs.pairs.push((key.to_string(), value)),
+                }
+            }
+            rest = next.trim_start();
+        }
+        (!attrs.is_empty()).then_some(attrs)
+    }
+
+    /// 取出文本结尾的属性块，返回去除属性块后的文本
+    pub(crate) fn split_trailing(text: &str) -> Option<(&str, Self)> {
+        let text = text.trim_end();
+        if !text.ends_with('}') {
+            return None;
+        }
+        let start = text.rfind('{')?;
+        let attrs = Self::parse(&text[start..])?;
+        Some((text[..start].trim_end(), attrs))
+    }
+}
+
+/// 切分出一项，引号内的空白不作为分隔
+fn split_item(src: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    for (idx, c) in src.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c.is_whitespace() => return Some((&src[..idx], &src[idx..])),
+            None => {}
+        }
+    }
+    // 引号未闭合
+    quote.is_none().then_some((src, ""))
+}
+
+/// 属性名与 id、类名只能包含字母数字与 `-` `_` `:` `.`
+fn name(src: &str) -> Option<&str> {
+    let valid = src.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && src
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'));
+    valid.then_some(src)
+}
+
+fn unquote(value: &str) -> String {
+    for q in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(q).and_then(|v| v.strip_suffix(q)) {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let attrs = Attributes::parse("{#intro .a .b data-x=1 title=\"two words\" class='c d'}");
+        assert_eq!(
+            attrs,
+            Some(Attributes {
+                id: Some(String::from("intro")),
+                classes: ["a", "b", "c", "d"].map(String::from).to_vec(),
+                pairs: vec![
+                    (String::from("data-x"), String::from("1")),
+                    (String::from("title"), String::from("two words")),
+                ],
+            })
+        );
+        assert!(Attributes::parse("{: .x}").is_some());
+        // 不是属性块的花括号
+        assert_eq!(Attributes::parse("{}"), None);
+        assert_eq!(Attributes::parse("{a, b}"), None);
+        assert_eq!(Attributes::parse("{x=\"open}"), None);
+        assert_eq!(Attributes::parse("{#1}"), None);
+    }
+
+    #[test]
+    fn test_split_trailing() {
+        let (text, attrs) = Attributes::split_trailing("Title {#t} ").unwrap();
+        assert_eq!(text, "Title");
+        assert_eq!(attrs.id.as_deref(), Some("t"));
+        assert_eq!(Attributes::split_trailing("set {a} b"), None);
+    }
+}
@@ -0,0 +1,1160 @@
+//! 块级语法分析
+//!
+//! 以行为单位识别块结构，块内文本交由行内分析处理
+
+use std::{borrow::Cow, ops::Range};
+
+use crate::lexical::{
+    tokens::{
+        idented::columns,
+        list::{OrderList, UnorderList},
+        titles::TitleToken,
+    },
+    LexicalLoader, Token,
+};
+
+use super::{
+    abbr,
+    ast::{Node, NodeKind},
+    attributes::Attributes,
+    html::HtmlBlockKind,
+    inline::parse_inline,
+    options::Options,
+};
+
+pub(super) fn parse_blocks(lines: &[&str], opts: &Options) -> Vec<Node> {
+    parse_spans(lines, opts)
+        .into_iter()
+        .map(|(node, _)| node)
+        .collect()
+}
+
+/// 块级节点与其占用的行范围
+pub(super) type Spanned = (Node, Range<usize>);
+
+pub(super) fn parse_spans(lines: &[&str], opts: &Options) -> Vec<Spanned> {
+    let mut blocks = Vec::new();
+    let mut paragraph = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        // 空行 结束段落
+        if line.trim().is_empty() {
+            flush_paragraph(&mut paragraph, i, &mut blocks, opts);
+            i += 1;
+            continue;
+        }
+
+        let (indent, content) = strip_indent(line);
+        if indent < 4 {
+            // 单独一行的属性块附加在紧邻的前一个块上
+            if let Some(attrs) = Attributes::parse(content.trim_end()) {
+                flush_paragraph(&mut paragraph, i, &mut blocks, opts);
+                if let Some((node, span)) = blocks.last_mut().filter(|(_, s)| s.end == i) {
+                    node.attrs.extend(attrs);
+                    span.end = i + 1;
+                    i += 1;
+                    continue;
+                }
+            }
+
+            let used = if quote_marker(content, indent).is_some() {
+                let (node, used) = block_quote(&lines[i..], opts);
+                Some((node, used))
+            } else if let Some(fence) = Fence::open(content, indent) {
+                let (node, used) = fence.collect(&lines[i + 1..]);
+                Some((node, used + 1))
+            } else if let Some(container) = Container::open(content, opts) {
+                Some(container.collect(&lines[i + 1..], opts))
+            } else if let Some(kind) = HtmlBlockKind::start(content)
+                .filter(|k| paragraph.is_empty() || k.can_interrupt_paragraph())
+            {
+                let used = html_block_len(kind, &lines[i..]);
+                let literal = lines[i..i + used].join("\n") + "\n";
+                Some((Node::new(NodeKind::HtmlBlock { literal }), used))
+            } else if let Some((abbr, title)) =
+                abbr::definition(content).filter(|_| opts.abbreviations)
+            {
+                Some((Node::new(NodeKind::Abbreviation { abbr, title }), 1))
+            } else if is_thematic_break(content) {
+                Some((Node::new(NodeKind::ThematicBreak), 1))
+            } else if let Some((marker, rest)) = list_marker(content) {
+                // 打断段落的列表项不能为空，有序列表只能从 1 开始
+                if paragraph.is_empty()
+                    || (!rest.trim().is_empty() && matches!(marker.start, None | Some(1)))
+                {
+                    Some(list(&lines[i..], opts))
+                } else {
+                    None
+                }
+            } else {
+                atx_heading(content, opts).map(|heading| (heading, 1))
+            };
+            if let Some((node, used)) = used {
+                flush_paragraph(&mut paragraph, i, &mut blocks, opts);
+                blocks.push((node, i..i + used));
+                i += used;
+                continue;
+            }
+        }
+
+        // 缩进代码不能打断段落
+        if indent >= 4 && paragraph.is_empty() {
+            let used = indented_code_len(&lines[i..]);
+            let literal = lines[i..i + used]
+                .iter()
+                .map(|line| strip_columns(line, 0, 4) + "\n")
+                .collect();
+            let info = String::new();
+            blocks.push((
+                Node::new(NodeKind::CodeBlock { info, literal }),
+                i..i + used,
+            ));
+            i += used;
+            continue;
+        }
+
+        paragraph.push(line);
+        i += 1;
+    }
+    flush_paragraph(&mut paragraph, i, &mut blocks, opts);
+    blocks
+}
+
+/// 缩进代码块占用的行数，包含内部的空行，不包含结尾的空行
+fn indented_code_len(lines: &[&str]) -> usize {
+    let mut used = 0;
+    for (idx, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if strip_indent(line).0 < 4 {
+            break;
+        }
+        used = idx + 1;
+    }
+    used
+}
+
+/// 输出段落，`end` 为段落之后的行号
+fn flush_paragraph(
+    paragraph: &mut Vec<&str>,
+    end: usize,
+    blocks: &mut Vec<Spanned>,
+    opts: &Options,
+) {
+    if paragraph.is_empty() {
+        return;
+    }
+    let start = end - paragraph.len();
+    // 行末空格用于判断强制换行，只去除最后一行的
+    let mut text = paragraph
+        .drain(..)
+        .map(str::trim_start)
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.truncate(text.trim_end().len());
+    blocks.push((
+        Node::with_children(NodeKind::Paragraph, parse_inline(&text, opts)),
+        start..end,
+    ));
+}
+
+/// 引用块，返回节点与占用的行数
+///
+/// 引用内的段落可以在没有 `>` 的行上延续
+fn block_quote(lines: &[&str], opts: &Options) -> (Node, usize) {
+    let mut inner = Vec::new();
+    let mut open = OpenBlocks::default();
+    for line in lines {
+        let (indent, content) = strip_indent(line);
+        let line = if let Some(rest) = quote_marker(content, indent).filter(|_| indent < 4) {
+            rest
+        } else if open.is_lazy(line, opts) {
+            Cow::Borrowed(*line)
+        } else {
+            break;
+        };
+        open.push(&line, opts);
+        inner.push(line);
+    }
+    let used = inner.len();
+    // `> [!NOTE]` 形式的提示块
+    if let Some((kind, title)) = inner.first().and_then(|l| alert(l, opts)) {
+        let children = parse_blocks(&as_lines(&inner[1..]), opts);
+        let kind = NodeKind::Admonition { kind, title };
+        return (Node::with_children(kind, children), used);
+    }
+    (
+        Node::with_children(NodeKind::BlockQuote, parse_blocks(&as_lines(&inner), opts)),
+        used,
+    )
+}
+
+/// 引用首行的 `[!KIND] title`，返回小写的类型与标题
+fn alert(line: &str, opts: &Options) -> Option<(String, String)> {
+    let rest = line.trim().strip_prefix("[!")?;
+    let (kind, title) = rest.split_once(']')?;
+    let kind = opts.admonition(kind)?;
+    Some((kind, title.trim().to_string()))
+}
+
+fn as_lines<'a>(inner: &'a [Cow<str>]) -> Vec<&'a str> {
+    inner.iter().map(|l| l.as_ref()).collect()
+}
+
+/// 逐行跟踪已收集的行中最后一个块，用于判断懒惰延续行
+///
+/// 识别规则与 `parse_spans` 一致，不需要重新分析已收集的行
+#[derive(Default)]
+struct OpenBlocks {
+    last: Last,
+}
+
+/// 最后一个块的状态
+#[derive(Default)]
+enum Last {
+    // 没有块或位于空行之后
+    #[default]
+    Blank,
+    // 块在上一行结束，属性块附加在其上
+    Closed,
+    // 属性块附加在最后一个块上，记录该块是否以段落结尾
+    Attached(bool),
+    Paragraph,
+    // 缩进代码，记录上一行是否为空行
+    Code {
+        blank: bool,
+    },
+    Fence(Fence),
+    Html(HtmlBlockKind),
+    Container {
+        len: usize,
+        depth: usize,
+        inner: Box<OpenBlocks>,
+    },
+    // 提示块的内容不包含首行，懒惰延续仍按包含首行的内容判断
+    Quote {
+        inner: Box<OpenBlocks>,
+        body: Option<Box<OpenBlocks>>,
+    },
+    Item {
+        column: usize,
+        // 标记后为空
+        blank: bool,
+        lines: usize,
+        last_blank: bool,
+        inner: Box<OpenBlocks>,
+    },
+}
+
+impl OpenBlocks {
+    /// 最后一个块（包括容器内部）是否为未结束的段落
+    fn is_open(&self) -> bool {
+        match &self.last {
+            Last::Paragraph => true,
+            Last::Attached(open) => *open,
+            Last::Container { inner, .. } | Last::Item { inner, .. } => inner.is_open(),
+            Last::Quote { inner, body } => body.as_ref().unwrap_or(inner).is_open(),
+            _ => false,
+        }
+    }
+
+    /// 该行能否作为容器内段落的延续
+    fn is_lazy(&self, line: &str, opts: &Options) -> bool {
+        !line.trim().is_empty() && !starts_block(line, opts) && self.is_open()
+    }
+
+    /// 加入容器内的一行
+    fn push(&mut self, line: &str, opts: &Options) {
+        let blank = line.trim().is_empty();
+        match &mut self.last {
+            Last::Quote { inner, body } => {
+                let (indent, content) = strip_indent(line);
+                let rest = match quote_marker(content, indent).filter(|_| indent < 4) {
+                    Some(rest) => rest,
+                    None if inner.is_lazy(line, opts) => Cow::Borrowed(line),
+                    None => return self.start(line, opts),
+                };
+                inner.push(&rest, opts);
+                if let Some(body) = body {
+                    body.push(&rest, opts);
+                }
+            }
+            Last::Item {
+                column,
+                blank: empty,
+                lines,
+                last_blank,
+                inner,
+            } => {
+                // 列表项最多以一个空行开始
+                let stripped = if blank && *lines == 1 && *empty {
+                    None
+                } else if blank || strip_indent(line).0 >= *column {
+                    Some(strip_columns(line, 0, *column))
+                } else {
+                    inner.is_lazy(line, opts).then_some(Cow::Borrowed(line))
+                };
+                let Some(stripped) = stripped else {
+                    // 结尾的空行不属于列表项
+                    self.last = if *last_blank {
+                        Last::Blank
+                    } else {
+                        Last::Closed
+                    };
+                    return self.start(line, opts);
+                };
+                inner.push(&stripped, opts);
+                *lines += 1;
+                *last_blank = blank;
+            }
+            Last::Fence(fence) => {
+                if fence.is_close(line) {
+                    self.last = Last::Closed;
+                }
+            }
+            Last::Html(kind) if kind.end_at_blank() => {
+                if blank {
+                    self.last = Last::Blank;
+                }
+            }
+            Last::Html(kind) => {
+                if kind.is_end(line) {
+                    self.last = Last::Closed;
+                }
+            }
+            Last::Container { len, depth, inner } => {
+                let (indent, content) = strip_indent(line);
+                if indent < 4 && Container::is_opener(content) {
+                    *depth += 1;
+                } else if let Some(close) = Container::close_len(line) {
+                    if *depth > 0 {
+                        *depth -= 1;
+                    } else if close >= *len {
+                        self.last = Last::Closed;
+                        return;
+                    }
+                }
+                inner.push(line, opts);
+            }
+            Last::Code { blank: last_blank } => {
+                if blank || strip_indent(line).0 >= 4 {
+                    *last_blank = blank;
+                } else {
+                    self.last = if *last_blank {
+                        Last::Blank
+                    } else {
+                        Last::Closed
+                    };
+                    self.start(line, opts);
+                }
+            }
+            Last::Blank | Last::Closed | Last::Attached(_) | Last::Paragraph => {
+                self.start(line, opts)
+            }
+        }
+    }
+
+    /// 不属于最后一个容器块的行，与 `parse_spans` 的识别顺序相同
+    fn start(&mut self, line: &str, opts: &Options) {
+        if line.trim().is_empty() {
+            self.last = Last::Blank;
+            return;
+        }
+        let paragraph = matches!(self.last, Last::Paragraph);
+        let (indent, content) = strip_indent(line);
+        if indent < 4 {
+            if Attributes::parse(content.trim_end()).is_some() && !matches!(self.last, Last::Blank)
+            {
+                self.last = Last::Attached(self.is_open());
+                return;
+            }
+            let last = if let Some(rest) = quote_marker(content, indent) {
+                let mut inner = Box::<OpenBlocks>::default();
+                inner.push(&rest, opts);
+                let body = alert(&rest, opts).map(|_| Box::default());
+                Some(Last::Quote { inner, body })
+            } else if let Some(fence) = Fence::open(content, indent) {
+                Some(Last::Fence(fence))
+            } else if let Some(container) = Container::open(content, opts) {
+                Some(Last::Container {
+                    len: container.len,
+                    depth: 0,
+                    inner: Box::default(),
+                })
+            } else if let Some(kind) =
+                HtmlBlockKind::start(content).filter(|k| !paragraph || k.can_interrupt_paragraph())
+            {
+                // 同一行内结束的 html 块
+                if !kind.end_at_blank() && kind.is_end(line) {
+                    Some(Last::Closed)
+                } else {
+                    Some(Last::Html(kind))
+                }
+            } else if (opts.abbreviations && abbr::definition(content).is_some())
+                || is_thematic_break(content)
+            {
+                Some(Last::Closed)
+            } else if let Some((marker, rest)) = list_marker(content) {
+                if !paragraph || (!rest.trim().is_empty() && matches!(marker.start, None | Some(1)))
+                {
+                    let (first, column, blank) = item_start(line);
+                    let mut inner = Box::<OpenBlocks>::default();
+                    inner.push(&first, opts);
+                    Some(Last::Item {
+                        column,
+                        blank,
+                        lines: 1,
+                        last_blank: false,
+                        inner,
+                    })
+                } else {
+                    None
+                }
+            } else {
+                atx_heading(content, opts).map(|_| Last::Closed)
+            };
+            if let Some(last) = last {
+                self.last = last;
+                return;
+            }
+        }
+        self.last = if indent >= 4 && !paragraph {
+            Last::Code { blank: false }
+        } else {
+            Last::Paragraph
+        };
+    }
+}
+
+/// 列表标记
+struct ListMarker {
+    // 无序列表为 `-` `*` `+`，有序列表为 `.` `)`
+    marker: char,
+    // 有序列表的序号
+    start: Option<u64>,
+    len: usize,
+}
+
+impl ListMarker {
+    fn same_list(&self, other: &Self) -> bool {
+        self.marker == other.marker && self.start.is_some() == other.start.is_some()
+    }
+}
+
+/// 依据词法分析的列表单元识别列表标记，返回标记与剩余内容
+fn list_marker(content: &str) -> Option<(ListMarker, &str)> {
+    let Token(token, raw) = LexicalLoader::first_token(content)?;
+    let (marker, start) = if let Some(list) = token.downcast_ref::<UnorderList>() {
+        (list.marker, None)
+    } else if let Some(list) = token.downcast_ref::<OrderList>() {
+        (list.delimiter, Some(list.number))
+    } else {
+        return None;
+    };
+    let rest = &content[raw.len()..];
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let len = raw.len();
+    Some((ListMarker { marker, start, len }, rest))
+}
+
+/// 列表，由连续的同类列表项组成
+fn list(lines: &[&str], opts: &Options) -> (Node, usize) {
+    let mut items = Vec::new();
+    let mut first: Option<ListMarker> = None;
+    let mut tight = true;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let (indent, content) = strip_indent(lines[i]);
+        let marker = match list_marker(content) {
+            Some((marker, _)) if indent < 4 && !is_thematic_break(content) => marker,
+            _ => break,
+        };
+        if first.as_ref().is_some_and(|f| !f.same_list(&marker)) {
+            break;
+        }
+        first.get_or_insert(marker);
+
+        let (inner, used) = list_item(&lines[i..], opts);
+        let blocks = parse_spans(&as_lines(&inner), opts);
+        // 列表项的子块之间有空行
+        if blocks.windows(2).any(|w| w[1].1.start > w[0].1.end) {
+            tight = false;
+        }
+        items.push(Node::with_children(
+            NodeKind::ListItem,
+            blocks.into_iter().map(|(node, _)| node).collect(),
+        ));
+        i += used;
+
+        // 列表项之间的空行
+        let blanks = lines[i..]
+            .iter()
+            .take_while(|l| l.trim().is_empty())
+            .count();
+        let next = lines.get(i + blanks).map(|l| strip_indent(l));
+        let next = next.filter(|(indent, content)| *indent < 4 && !is_thematic_break(content));
+        match next.and_then(|(_, content)| list_marker(content)) {
+            Some((marker, _)) if first.as_ref().is_some_and(|f| f.same_list(&marker)) => {
+                tight &= blanks == 0;
+                i += blanks;
+            }
+            _ => break,
+        }
+    }
+
+    let first = first.expect("list must start with a marker");
+    let kind = NodeKind::List {
+        marker: first.marker,
+        start: first.start,
+        tight,
+    };
+    (Node::with_children(kind, items), i)
+}
+
+/// 列表项，返回去除缩进后的内容行与占用的行数
+///
+/// 内容列由标记之后的空白列数决定，后续行缩进达到内容列时属于该项
+fn list_item<'a>(lines: &[&'a str], opts: &Options) -> (Vec<Cow<'a, str>>, usize) {
+    let (first, column, blank) = item_start(lines[0]);
+    let mut open = OpenBlocks::default();
+    open.push(&first, opts);
+    let mut inner = vec![first];
+    for line in &lines[1..] {
+        let line = if line.trim().is_empty() {
+            // 列表项最多以一个空行开始
+            if inner.len() == 1 && blank {
+                break;
+            }
+            strip_columns(line, 0, column)
+        } else if strip_indent(line).0 >= column {
+            strip_columns(line, 0, column)
+        } else if open.is_lazy(line, opts) {
+            Cow::Borrowed(*line)
+        } else {
+            break;
+        };
+        open.push(&line, opts);
+        inner.push(line);
+    }
+    // 结尾的空行不属于列表项
+    while inner.len() > 1 && inner.last().is_some_and(|l| l.trim().is_empty()) {
+        inner.pop();
+    }
+    let used = inner.len();
+    (inner, used)
+}
+
+/// 列表项的首行，返回去除标记后的内容、内容列以及标记后是否为空
+fn item_start(line: &str) -> (Cow<'_, str>, usize, bool) {
+    let (indent, content) = strip_indent(line);
+    let (marker, rest) = list_marker(content).expect("list item must start with a marker");
+    let base = indent + marker.len;
+    let spaces = columns(base, rest.chars().take_while(|c| matches!(c, ' ' | '\t'))) - base;
+    // 标记后为空行或超过 4 列空白（内容为缩进代码）时，内容列为标记后一列
+    let blank = rest.trim().is_empty();
+    let width = if blank || spaces > 4 { 1 } else { spaces };
+    let first = if blank {
+        Cow::Borrowed("")
+    } else {
+        strip_columns(rest, base, width)
+    };
+    (first, base + width, blank)
+}
+
+/// 该行是否开始了一个新的块
+fn starts_block(line: &str, opts: &Options) -> bool {
+    let (indent, content) = strip_indent(line);
+    indent < 4
+        && (quote_marker(content, indent).is_some()
+            || Fence::open(content, indent).is_some()
+            || Container::open(content, opts).is_some()
+            || HtmlBlockKind::start(content).is_some()
+            || (opts.abbreviations && abbr::definition(content).is_some())
+            || is_thematic_break(content)
+            || list_marker(content).is_some()
+            || atx_heading(content, opts).is_some())
+}
+
+/// 段落中的行是否可能被识别为块的开始，包括单独一行的属性块
+///
+/// 不依赖可选语法的开关，任意 `:::` 都视为提示块，用于格式化时转义
+pub(crate) fn may_start_block(line: &str) -> bool {
+    let (indent, content) = strip_indent(line);
+    indent < 4
+        && (Attributes::parse(content.trim_end()).is_some()
+            || content.starts_with(":::")
+            || starts_block(line, &Options::new().abbreviations(true)))
+}
+
+/// 去除引用标记 `>` 与其后的一列空白，`indent` 为标记所在的列
+fn quote_marker(content: &str, indent: usize) -> Option<Cow<'_, str>> {
+    let rest = content.strip_prefix('>')?;
+    Some(strip_columns(rest, indent + 1, 1))
+}
+
+/// 去除行首空白，返回缩进列数与剩余内容
+fn strip_indent(line: &str) -> (usize, &str) {
+    let content = line.trim_start_matches([' ', '\t']);
+    (
+        columns(0, line[..line.len() - content.len()].chars()),
+        content,
+    )
+}
+
+/// 去除 `text` 开头 `n` 列的空白，`col` 为 `text` 开始的列
+///
+/// 开头的制表符展开为空格，被部分占用的制表符剩余的列保留为空格
+fn strip_columns(text: &str, col: usize, n: usize) -> Cow<'_, str> {
+    let rest = text.trim_start_matches([' ', '\t']);
+    let whitespace = &text[..text.len() - rest.len()];
+    if !whitespace.contains('\t') {
+        return Cow::Borrowed(&text[n.min(whitespace.len())..]);
+    }
+    let width = columns(col, whitespace.chars()) - col;
+    Cow::Owned(" ".repeat(width.saturating_sub(n)) + rest)
+}
+
+/// html 块占用的行数
+fn html_block_len(kind: HtmlBlockKind, lines: &[&str]) -> usize {
+    if kind.end_at_blank() {
+        lines
+            .iter()
+            .position(|l| l.trim().is_empty())
+            .unwrap_or(lines.len())
+    } else {
+        lines
+            .iter()
+            .position(|l| kind.is_end(l))
+            .map(|p| p + 1)
+            .unwrap_or(lines.len())
+    }
+}
+
+fn is_thematic_break(content: &str) -> bool {
+    let mut marks = content.chars().filter(|c| !c.is_whitespace());
+    match marks.next() {
+        Some(c @ ('-' | '*' | '_')) => marks.clone().all(|m| m == c) && marks.count() >= 2,
+        _ => false,
+    }
+}
+
+/// `#` 标题，依据词法分析的 title 单元
+fn atx_heading(content: &str, opts: &Options) -> Option<Node> {
+    let Token(token, raw) = LexicalLoader::first_token(content)?;
+    let level = token.downcast_ref::<TitleToken>()?.level;
+    let rest = &content[raw.len()..];
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    // 去除结尾的 `#` 序列
+    let rest = rest.trim();
+    let without = rest.trim_end_matches('#');
+    let text = if without.is_empty() {
+        without
+    } else if without.ends_with([' ', '\t']) {
+        without.trim_end()
+    } else {
+        rest
+    };
+    let (text, attrs) = Attributes::split_trailing(text).unwrap_or((text, Attributes::default()));
+    let node = Node::with_children(NodeKind::Heading { level }, parse_inline(text, opts));
+    Some(node.with_attrs(attrs))
+}
+
+/// 围栏代码块
+struct Fence {
+    ch: char,
+    len: usize,
+    indent: usize,
+    info: String,
+    attrs: Attributes,
+}
+
+impl Fence {
+    fn open(content: &str, indent: usize) -> Option<Self> {
+        let ch = content.chars().next().filter(|c| *c == '`' || *c == '~')?;
+        let len = content.chars().take_while(|c| *c == ch).count();
+        if len < 3 {
+            return None;
+        }
+        let info = content[len..].trim();
+        if ch == '`' && info.contains('`') {
+            return None;
+        }
+        let (info, attrs) =
+            Attributes::split_trailing(info).unwrap_or((info, Attributes::default()));
+        Some(Self {
+            ch,
+            len,
+            indent,
+            info: info.to_string(),
+            attrs,
+        })
+    }
+
+    fn is_close(&self, line: &str) -> bool {
+        let (indent, content) = strip_indent(line);
+        let len = content.chars().take_while(|c| *c == self.ch).count();
+        indent < 4 && len >= self.len && content[len..].trim().is_empty()
+    }
+
+    /// 收集代码块内容，返回节点与占用的行数（含结束围栏）
+    fn collect(self, lines: &[&str]) -> (Node, usize) {
+        let mut literal = String::new();
+        let mut used = 0;
+        for line in lines {
+            used += 1;
+            if self.is_close(line) {
+                break;
+            }
+            // 去除与开始围栏相同宽度的缩进
+            literal.push_str(&strip_columns(line, 0, self.indent));
+            literal.push('\n');
+        }
+        let node = Node::new(NodeKind::CodeBlock {
+            info: self.info,
+            literal,
+        });
+        (node.with_attrs(self.attrs), used)
+    }
+}
+
+/// `::: kind title` 围栏提示块
+struct Container {
+    len: usize,
+    kind: String,
+    title: String,
+}
+
+impl Container {
+    fn open(content: &str, opts: &Options) -> Option<Self> {
+        let len = content.chars().take_while(|c| *c == ':').count();
+        if len < 3 {
+            return None;
+        }
+        let rest = content[len..].trim();
+        let (kind, title) = rest.split_once([' ', '\t']).unwrap_or((rest, ""));
+        let kind = opts.admonition(kind)?;
+        Some(Self {
+            len,
+            kind,
+            title: title.trim().to_string(),
+        })
+    }
+
+    /// 任意类型的开始行，`:::` 之后带有类型名称
+    fn is_opener(content: &str) -> bool {
+        let len = content.chars().take_while(|c| *c == ':').count();
+        len >= 3 && !content[len..].trim().is_empty()
+    }
+
+    /// 只由 `:` 组成的行，返回其长度
+    fn close_len(line: &str) -> Option<usize> {
+        let (indent, content) = strip_indent(line);
+        let content = content.trim_end();
+        let len = content.chars().take_while(|c| *c == ':').count();
+        (indent < 4 && len >= 3 && len == content.len()).then_some(len)
+    }
+
+    /// 收集内部的行，返回节点与占用的行数（含开始与结束行）
+    ///
+    /// 内部可以嵌套提示块，未结束的提示块延续到末尾
+    fn collect(self, lines: &[&str], opts: &Options) -> (Node, usize) {
+        let mut depth = 0;
+        let mut end = lines.len();
+        let mut used = lines.len();
+        for (idx, line) in lines.iter().enumerate() {
+            let (indent, content) = strip_indent(line);
+            // 未知类型的开始行同样需要对应的结束行
+            if indent < 4 && Self::is_opener(content) {
+                depth += 1;
+            } else if let Some(len) = Self::close_len(line) {
+                if depth > 0 {
+                    depth -= 1;
+                } else if len >= self.len {
+                    end = idx;
+                    used = idx + 1;
+                    break;
+                }
+            }
+        }
+        let kind = NodeKind::Admonition {
+            kind: self.kind,
+            title: self.title,
+        };
+        let node = Node::with_children(kind, parse_blocks(&lines[..end], opts));
+        (node, used + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::syntax::{parse, parse_with};
+
+    use super::*;
+
+    fn kinds(node: &Node) -> Vec<&NodeKind> {
+        node.children.iter().map(|n| &n.kind).collect()
+    }
+
+    #[test]
+    fn test_heading() {
+        let doc = parse("# title\n## sub *title* ##\n#not title\n####### seven");
+        assert_eq!(doc.children[0].kind, NodeKind::Heading { level: 1 });
+        assert_eq!(doc.children[0].text_content(), "title");
+        assert_eq!(doc.children[1].kind, NodeKind::Heading { level: 2 });
+        assert_eq!(doc.children[1].children[1].kind, NodeKind::Emphasis);
+        assert_eq!(doc.children[2].kind, NodeKind::Paragraph);
+        assert_eq!(doc.children[2].text_content(), "#not title\n####### seven");
+    }
+
+    #[test]
+    fn test_fence_and_break() {
+        let doc = parse("para\n***\n```rust\nfn main() {}\n\n```\nafter");
+        assert_eq!(doc.children[1].kind, NodeKind::ThematicBreak);
+        assert_eq!(
+            doc.children[2].kind,
+            NodeKind::CodeBlock {
+                info: String::from("rust"),
+                literal: String::from("fn main() {}\n\n")
+            }
+        );
+        assert_eq!(doc.children[3].text_content(), "after");
+    }
+
+    #[test]
+    fn test_line_break() {
+        let doc = parse("a  \n  b\\\nc \nd  \n\n  e  ");
+        assert_eq!(doc.children.len(), 2);
+        assert_eq!(
+            kinds(&doc.children[0]),
+            vec![
+                &NodeKind::Text {
+                    value: String::from("a")
+                },
+                &NodeKind::HardBreak,
+                &NodeKind::Text {
+                    value: String::from("b")
+                },
+                &NodeKind::HardBreak,
+                &NodeKind::Text {
+                    value: String::from("c")
+                },
+                &NodeKind::SoftBreak,
+                &NodeKind::Text {
+                    value: String::from("d")
+                },
+            ]
+        );
+        assert_eq!(doc.children[1].children, vec![Node::text("e")]);
+    }
+
+    #[test]
+    fn test_block_quote() {
+        let doc = parse("> # title\n> > nested\nlazy\n>\n> - item\n\nout");
+        let quote = &doc.children[0];
+        assert_eq!(quote.kind, NodeKind::BlockQuote);
+        assert_eq!(quote.children[0].kind, NodeKind::Heading { level: 1 });
+        let nested = &quote.children[1];
+        assert_eq!(nested.kind, NodeKind::BlockQuote);
+        assert_eq!(nested.text_content(), "nested\nlazy");
+        assert_eq!(
+            quote.children[2].kind,
+            NodeKind::List {
+                marker: '-',
+                start: None,
+                tight: true
+            }
+        );
+        assert_eq!(doc.children[1].text_content(), "out");
+
+        // 延续行只能延续段落
+        let doc = parse("> ```\n> code\nafter\n>>deep\n***");
+        assert_eq!(doc.children.len(), 4);
+        assert_eq!(
+            doc.children[0].children[0].kind,
+            NodeKind::CodeBlock {
+                info: String::new(),
+                literal: String::from("code\n")
+            }
+        );
+        assert_eq!(doc.children[1].text_content(), "after");
+        assert_eq!(doc.children[2].children[0].kind, NodeKind::BlockQuote);
+        assert_eq!(doc.children[3].kind, NodeKind::ThematicBreak);
+    }
+
+    #[test]
+    fn test_lazy_nested_quote() {
+        // 延续行不重新分析已收集的行，嵌套很深时也不会超时
+        let depth = 16;
+        let input = "> ".repeat(depth) + "start\n" + &"lazy\n".repeat(1000);
+        let doc = parse(&input);
+        let mut node = &doc;
+        for _ in 0..depth {
+            assert_eq!(node.children.len(), 1);
+            node = &node.children[0];
+            assert_eq!(node.kind, NodeKind::BlockQuote);
+        }
+        assert_eq!(kinds(node), vec![&NodeKind::Paragraph]);
+        assert_eq!(node.text_content().lines().count(), 1001);
+    }
+
+    #[test]
+    fn test_list() {
+        // lexical::test::test_list 的输入
+        let doc =
+            parse("* abbabb\n* bbcbxx\n    12222332. abbaab\n    >> emmc?\n        codeblock");
+        assert_eq!(doc.children.len(), 1);
+        let list = &doc.children[0];
+        assert_eq!(
+            list.kind,
+            NodeKind::List {
+                marker: '*',
+                start: None,
+                tight: true
+            }
+        );
+        assert_eq!(list.children[0].text_content(), "abbabb");
+        let item = &list.children[1];
+        // 序号不为 1 的有序列表不能打断段落
+        assert_eq!(item.children[0].text_content(), "bbcbxx\n12222332. abbaab");
+        let quote = &item.children[1];
+        assert_eq!(quote.children[0].kind, NodeKind::BlockQuote);
+        assert_eq!(quote.text_content(), "emmc?\ncodeblock");
+
+        let doc = parse("* a\n\n  12222332) b\n  2) c\n\n     nested\n+ d");
+        let item = &doc.children[0].children[0];
+        assert_eq!(
+            item.children[1].kind,
+            NodeKind::List {
+                marker: ')',
+                start: Some(12222332),
+                tight: false
+            }
+        );
+        assert_eq!(item.children[1].children[1].children.len(), 2);
+        // 标记不同，开始新的列表
+        assert_eq!(doc.children[1].text_content(), "d");
+    }
+
+    #[test]
+    fn test_lazy_nested_list() {
+        let depth = 16;
+        let input = "- ".repeat(depth) + "start\n" + &"lazy\n".repeat(1000);
+        let doc = parse(&input);
+        let mut node = &doc;
+        for _ in 0..depth {
+            assert_eq!(node.children.len(), 1);
+            assert!(matches!(node.children[0].kind, NodeKind::List { .. }));
+            node = &node.children[0].children[0];
+            assert_eq!(node.kind, NodeKind::ListItem);
+        }
+        assert_eq!(kinds(node), vec![&NodeKind::Paragraph]);
+        assert_eq!(node.text_content().lines().count(), 1001);
+    }
+
+    #[test]
+    fn test_readme_example() {
+        let doc = parse(" * a\n * b\n    122. c\n    \n    >>ababa\n* d\n        codes");
+        let list = &doc.children[0];
+        assert_eq!(list.children.len(), 3);
+        let item = &list.children[1];
+        assert_eq!(item.children[0].text_content(), "b\n122. c");
+        assert_eq!(item.children[1].children[0].kind, NodeKind::BlockQuote);
+        assert_eq!(item.children[1].text_content(), "ababa");
+        assert_eq!(list.children[2].text_content(), "d\ncodes");
+    }
+
+    #[test]
+    fn test_list_interrupt() {
+        let doc = parse("text\n2. not list\n- \n1. list");
+        assert_eq!(doc.children[0].text_content(), "text\n2. not list\n-");
+        assert_eq!(
+            doc.children[1].kind,
+            NodeKind::List {
+                marker: '.',
+                start: Some(1),
+                tight: true
+            }
+        );
+
+        let doc = parse("- a\n- - -\n-\n\n  b");
+        assert_eq!(doc.children[1].kind, NodeKind::ThematicBreak);
+        // 以空行开始的列表项最多只有一个空行
+        assert_eq!(doc.children[2].children[0].children, vec![]);
+        assert_eq!(doc.children[3].text_content(), "b");
+    }
+
+    #[test]
+    fn test_tab_indent() {
+        let doc = parse("-\tfoo\n\n\tbar\n \t- nested\n>\tquote");
+        let item = &doc.children[0].children[0];
+        assert_eq!(item.children[0].text_content(), "foo");
+        assert_eq!(item.children[1].text_content(), "bar");
+        assert!(matches!(item.children[2].kind, NodeKind::List { .. }));
+        assert_eq!(doc.children[1].text_content(), "quote");
+
+        // 部分占用的制表符保留剩余的列
+        assert_eq!(strip_columns("\tfoo", 1, 1), "  foo");
+        assert_eq!(strip_columns("  \tfoo", 0, 2), "  foo");
+        assert_eq!(strip_columns("    foo", 0, 2), "  foo");
+        assert_eq!(strip_indent(" \t  foo"), (6, "foo"));
+    }
+
+    #[test]
+    fn test_indented_code() {
+        let doc = parse("    fn main() {\n\n  \t  let a;\n    }\n\ntext\n    not code");
+        assert_eq!(
+            doc.children[0].kind,
+            NodeKind::CodeBlock {
+                info: String::new(),
+                literal: String::from("fn main() {\n\n  let a;\n}\n")
+            }
+        );
+        assert_eq!(doc.children[1].text_content(), "text\nnot code");
+
+        // 列表项内的缩进代码与延续段落
+        let doc = parse("- a\n\n      code\n\n  para\n-     first code\n  next");
+        let item = &doc.children[0].children[0];
+        assert_eq!(
+            item.children[1].kind,
+            NodeKind::CodeBlock {
+                info: String::new(),
+                literal: String::from("code\n")
+            }
+        );
+        assert_eq!(item.children[2].kind, NodeKind::Paragraph);
+        let item = &doc.children[0].children[1];
+        assert_eq!(
+            item.children[0].kind,
+            NodeKind::CodeBlock {
+                info: String::new(),
+                literal: String::from("first code\n")
+            }
+        );
+        assert_eq!(item.children[1].text_content(), "next");
+    }
+
+    #[test]
+    fn test_html_block() {
+        let doc = parse("<div>\n*raw*\n</div>\n\ntext\n<!-- a\n\nb -->\n<custom>\n");
+        assert_eq!(
+            doc.children[0].kind,
+            NodeKind::HtmlBlock {
+                literal: String::from("<div>\n*raw*\n</div>\n")
+            }
+        );
+        // 注释可以打断段落，且可以包含空行
+        assert_eq!(doc.children[1].text_content(), "text");
+        assert_eq!(
+            doc.children[2].kind,
+            NodeKind::HtmlBlock {
+                literal: String::from("<!-- a\n\nb -->\n")
+            }
+        );
+        assert_eq!(
+            doc.children[3].kind,
+            NodeKind::HtmlBlock {
+                literal: String::from("<custom>\n")
+            }
+        );
+    }
+
+    #[test]
+    fn test_tag_not_interrupt() {
+        let doc = parse("text\n<custom>\nmore");
+        assert_eq!(doc.children.len(), 1);
+        assert_eq!(
+            doc.children[0].children[2].kind,
+            NodeKind::HtmlInline {
+                literal: String::from("<custom>")
+            }
+        );
+    }
+
+    fn admonition(kind: &str, title: &str) -> NodeKind {
+        NodeKind::Admonition {
+            kind: kind.to_string(),
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_alert() {
+        let doc = parse("> [!Note] Heads up\n> body\nlazy\n\n> [!unknown]\n> x");
+        let alert = &doc.children[0];
+        assert_eq!(alert.kind, admonition("note", "Heads up"));
+        assert_eq!(alert.children[0].text_content(), "body\nlazy");
+        assert_eq!(doc.children[1].kind, NodeKind::BlockQuote);
+    }
+
+    #[test]
+    fn test_container() {
+        let doc = parse(
+            "::: warning Be careful\n- a\n:::: tip\nnested\n::::\n:::\ntext\n::: danger\nopen",
+        );
+        assert_eq!(
+            kinds(&doc),
+            vec![
+                &admonition("warning", "Be careful"),
+                &NodeKind::Paragraph,
+                &admonition("danger", "")
+            ]
+        );
+        let outer = &doc.children[0];
+        assert_eq!(outer.children[1].kind, admonition("tip", ""));
+        assert_eq!(outer.children[1].children[0].text_content(), "nested");
+        assert_eq!(doc.children[2].children[0].text_content(), "open");
+
+        // 未知类型的内部块不会提前结束外层
+        let doc = parse("::: note\n::: spoiler\nx\n:::\ny\n:::\nafter");
+        assert_eq!(
+            kinds(&doc),
+            vec![&admonition("note", ""), &NodeKind::Paragraph]
+        );
+        assert_eq!(
+            doc.children[0].children[0].text_content(),
+            "::: spoiler\nx\n:::\ny"
+        );
+        assert_eq!(doc.children[1].text_content(), "after");
+
+        // 未知类型不是提示块
+        let doc = parse("::: spoiler\nx\n:::");
+        assert_eq!(kinds(&doc), vec![&NodeKind::Paragraph]);
+        let opts = Options::new().admonition_kinds(["Spoiler"]);
+        let doc = parse_with("::: spoiler\nx\n:::\n> [!NOTE]", &opts);
+        assert_eq!(
+            kinds(&doc),
+            vec![&admonition("spoiler", ""), &NodeKind::BlockQuote]
+        );
+    }
+
+    #[test]
+    fn test_attributes() {
+        let doc = parse(
+            "# Title {#top .main}\n```rust {.numberLines start=3}\nx\n```\npara\n{: .note}\n\n{.lonely}\n# a {b}",
+        );
+        let heading = &doc.children[0];
+        assert_eq!(heading.text_content(), "Title");
+        assert_eq!(heading.attrs.id.as_deref(), Some("top"));
+        assert_eq!(heading.attrs.classes, vec![String::from("main")]);
+        let code = &doc.children[1];
+        assert!(matches!(&code.kind, NodeKind::CodeBlock { info, .. } if info == "rust"));
+        assert_eq!(
+            code.attrs.pairs,
+            vec![(String::from("start"), String::from("3"))]
+        );
+        assert_eq!(doc.children[2].attrs.classes, vec![String::from("note")]);
+        // 前面没有紧邻的块时按普通文本处理
+        assert_eq!(doc.children[3].text_content(), "{.lonely}");
+        assert_eq!(doc.children[4].text_content(), "a {b}");
+        assert!(doc.children[4].attrs.is_empty());
+    }
+}
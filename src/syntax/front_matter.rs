@@ -0,0 +1,142 @@
+//! 文档开头的 front matter
+//!
+//! `---` 包围的为 YAML，`+++` 包围的为 TOML
+
+use std::fmt::{self, Display};
+
+use serde::de::DeserializeOwned;
+
+use super::ast::{Node, NodeKind};
+
+/// front matter 格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontMatterFormat {
+    Yaml,
+    Toml,
+}
+
+impl FrontMatterFormat {
+    pub(crate) fn fence(&self) -> &'static str {
+        match self {
+            FrontMatterFormat::Yaml => "---",
+            FrontMatterFormat::Toml => "+++",
+        }
+    }
+
+    /// 将原始文本反序列化为用户类型
+    pub fn deserialize<T: DeserializeOwned>(&self, raw: &str) -> Result<T, FrontMatterError> {
+        match self {
+            FrontMatterFormat::Yaml => serde_yaml::from_str(raw).map_err(FrontMatterError::Yaml),
+            FrontMatterFormat::Toml => toml::from_str(raw).map_err(FrontMatterError::Toml),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FrontMatterError {
+    Yaml(serde_yaml::Error),
+    Toml(toml::de::Error),
+}
+
+impl Display for FrontMatterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrontMatterError::Yaml(e) => write!(f, "invalid yaml front matter: {}", e),
+            FrontMatterError::Toml(e) => write!(f, "invalid toml front matter: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FrontMatterError {}
+
+/// 识别文档开头的 front matter，返回节点与占用的行数
+///
+/// 没有结束标记时不是 front matter
+pub(super) fn extract(lines: &[&str]) -> Option<(Node, usize)> {
+    let format = match lines.first()?.trim_end() {
+        "---" => FrontMatterFormat::Yaml,
+        "+++" => FrontMatterFormat::Toml,
+        _ => return None,
+    };
+    let end = lines[1..].iter().position(|line| {
+        let line = line.trim_end();
+        line == format.fence() || (format == FrontMatterFormat::Yaml && line == "...")
+    })? + 1;
+    let raw = lines[1..end]
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect();
+    Some((Node::new(NodeKind::FrontMatter { format, raw }), end + 1))
+}
+
+impl Node {
+    /// 将文档的 front matter 反序列化为用户类型，没有 front matter 时返回 `None`
+    pub fn front_matter<T: DeserializeOwned>(&self) -> Option<Result<T, FrontMatterError>> {
+        match &self.children.first()?.kind {
+            NodeKind::FrontMatter { format, raw } => Some(format.deserialize(raw)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::syntax::parse;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Meta {
+        title: String,
+        date: String,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_yaml() {
+        let doc = parse("---\ntitle: Hello\ndate: \"2021-10-01\"\ntags: [a, b]\n---\n# Hello\n");
+        assert_eq!(
+            doc.children[0].kind,
+            NodeKind::FrontMatter {
+                format: FrontMatterFormat::Yaml,
+                raw: String::from("title: Hello\ndate: \"2021-10-01\"\ntags: [a, b]\n")
+            }
+        );
+        assert_eq!(doc.children[1].kind, NodeKind::Heading { level: 1 });
+        let meta: Meta = doc.front_matter().unwrap().unwrap();
+        assert_eq!(
+            meta,
+            Meta {
+                title: String::from("Hello"),
+                date: String::from("2021-10-01"),
+                tags: vec![String::from("a"), String::from("b")],
+            }
+        );
+    }
+
+    #[test]
+    fn test_toml() {
+        let doc = parse("+++\ntitle = \"Hello\"\ndate = \"2021-10-01\"\n+++\n\ntext");
+        let meta: Meta = doc.front_matter().unwrap().unwrap();
+        assert_eq!(meta.title, "Hello");
+        assert!(meta.tags.is_empty());
+        assert_eq!(doc.children[1].text_content(), "text");
+
+        let err = doc.front_matter::<BTreeMap<String, u32>>().unwrap();
+        assert!(matches!(err, Err(FrontMatterError::Toml(_))));
+    }
+
+    #[test]
+    fn test_not_front_matter() {
+        // 不在文档开头或没有结束标记
+        let doc = parse("text\n\n---\ntitle: a\n---\n");
+        assert!(doc.front_matter::<Meta>().is_none());
+        let doc = parse("---\ntitle: a\n");
+        assert_eq!(doc.children[0].kind, NodeKind::ThematicBreak);
+        assert!(doc.front_matter::<Meta>().is_none());
+    }
+}
@@ -0,0 +1,444 @@
+//! html 块与行内 html 的识别
+
+/// CommonMark 中块级 html 的 7 种开始条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HtmlBlockKind {
+    /// `<script` `<pre` `<style` `<textarea`
+    Raw,
+    /// `<!--`
+    Comment,
+    /// `<?`
+    Instruction,
+    /// `<!` + 字母
+    Declaration,
+    /// `<![CDATA[`
+    CData,
+    /// 已知的块级标签
+    Block,
+    /// 其他完整的开始或结束标签
+    Tag,
+}
+
+const RAW_TAGS: [&str; 4] = ["script", "pre", "style", "textarea"];
+
+const BLOCK_TAGS: [&str; 62] = [
+    "address",
+    "article",
+    "aside",
+    "base",
+    "basefont",
+    "blockquote",
+    "body",
+    "caption",
+    "center",
+    "col",
+    "colgroup",
+    "dd",
+    "details",
+    "dialog",
+    "dir",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "frame",
+    "frameset",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "iframe",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "menu",
+    "menuitem",
+    "nav",
+    "noframes",
+    "ol",
+    "optgroup",
+    "option",
+    "p",
+    "param",
+    "search",
+    "section",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "track",
+    "ul",
+];
+
+impl HtmlBlockKind {
+    /// 判断一行（已去除不超过3个空格的缩进）是否开始一个 html 块
+    pub fn start(line: &str) -> Option<Self> {
+        let rest = line.strip_prefix('<')?;
+        let lower = rest.to_ascii_lowercase();
+
+        if let Some(tail) = RAW_TAGS.iter().find_map(|tag| lower.strip_prefix(tag)) {
+            if tail.is_empty() || tail.starts_with(|c: char| c.is_whitespace() || c == '>') {
+                return Some(Self::Raw);
+            }
+        }
+        if rest.starts_with("!--") {
+            Some(Self::Comment)
+        } else if rest.starts_with('?') {
+            Some(Self::Instruction)
+        } else if rest.starts_with("![CDATA[") {
+            Some(Self::CData)
+        } else if rest.starts_with('!') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            Some(Self::Declaration)
+        } else if is_block_tag(&lower) {
+            Some(Self::Block)
+        } else if (is_open_tag(line).or_else(|| is_closing_tag(line)))
+            .map(|len| line[len..].trim().is_empty())
+            .unwrap_or(false)
+        {
+            Some(Self::Tag)
+        } else {
+            None
+        }
+    }
+
+    /// 判断该行是否结束 html 块，以空行结束的类型由调用方处理
+    pub fn is_end(&self, line: &str) -> bool {
+        match self {
+            Self::Raw => {
+                let lower = line.to_ascii_lowercase();
+                RAW_TAGS
+                    .iter()
+                    .any(|tag| lower.contains(&format!("</{}>", tag)))
+            }
+            Self::Comment => line.contains("-->"),
+            Self::Instruction => line.contains("?>"),
+            Self::Declaration => line.contains('>'),
+            Self::CData => line.contains("]]>"),
+            Self::Block | Self::Tag => false,
+        }
+    }
+
+    /// 是否以空行结束
+    pub fn end_at_blank(&self) -> bool {
+        matches!(self, Self::Block | Self::Tag)
+    }
+
+    /// 是否可以打断段落
+    pub fn can_interrupt_paragraph(&self) -> bool {
+        !matches!(self, Self::Tag)
+    }
+}
+
+/// `lower` 为 `<` 之后的小写内容
+fn is_block_tag(lower: &str) -> bool {
+    let name = lower.strip_prefix('/').unwrap_or(lower);
+    BLOCK_TAGS.iter().any(|tag| {
+        name.strip_prefix(tag)
+            .map(|tail| {
+                tail.is_empty()
+                    || tail.starts_with(|c: char| c.is_whitespace() || c == '>')
+                    || tail.starts_with("/>")
+            })
+            .unwrap_or(false)
+    })
+}
+
+/// 解析得到的 html 标签
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlTag {
+    /// 小写的标签名称
+    pub name: String,
+    /// 属性名称与属性值，属性值保留原始写法
+    pub attrs: Vec<(String, Option<String>)>,
+    pub closing: bool,
+    pub self_closing: bool,
+}
+
+/// 解析开头的开始或结束标签，返回标签与字节长度
+pub fn parse_tag(src: &str) -> Option<(HtmlTag, usize)> {
+    let mut cur = Cursor::new(src);
+    cur.eat('<')?;
+    let closing = cur.eat('/').is_some();
+    let name = cur.tag_name()?.to_ascii_lowercase();
+    let mut tag = HtmlTag {
+        name,
+        attrs: vec![],
+        closing,
+        self_closing: false,
+    };
+    if closing {
+        cur.spaces();
+        cur.eat('>')?;
+        return Some((tag, cur.pos));
+    }
+    loop {
+        let spaced = cur.spaces();
+        if cur.eat('/').is_some() {
+            cur.eat('>')?;
+            tag.self_closing = true;
+            break;
+        }
+        if cur.eat('>').is_some() {
+            break;
+        }
+        if !spaced {
+            return None;
+        }
+        tag.attrs.push(cur.attribute()?);
+    }
+    Some((tag, cur.pos))
+}
+
+/// 识别开头的开始标签，返回标签的字节长度
+pub fn is_open_tag(src: &str) -> Option<usize> {
+    parse_tag(src)
+        .filter(|(tag, _)| !tag.closing)
+        .map(|(_, len)| len)
+}
+
+/// 识别开头的结束标签，返回标签的字节长度
+pub fn is_closing_tag(src: &str) -> Option<usize> {
+    parse_tag(src)
+        .filter(|(tag, _)| tag.closing)
+        .map(|(_, len)| len)
+}
+
+/// 判断 `<...>` 整体是否为合法的行内 html
+pub fn is_inline_html(raw: &str) -> bool {
+    if let Some(inner) = raw.strip_prefix("<!--").and_then(|s| s.strip_suffix("-->")) {
+        // 注释内容不能以 `>` `->` 开头
+        return !inner.starts_with('>') && !inner.starts_with("->");
+    }
+    if raw.starts_with("<?") {
+        return raw.ends_with("?>") && raw.len() >= 4;
+    }
+    if raw.starts_with("<![CDATA[") {
+        return raw.ends_with("]]>");
+    }
+    if let Some(decl) = raw.strip_prefix("<!") {
+        return decl.starts_with(|c: char| c.is_ascii_alphabetic()) && raw.ends_with('>');
+    }
+    is_open_tag(raw)
+        .or_else(|| is_closing_tag(raw))
+        .map(|len| len == raw.len())
+        .unwrap_or(false)
+}
+
+/// 尖括号内是否为 URI 自动链接
+pub fn is_uri_autolink(inner: &str) -> bool {
+    let (scheme, rest) = match inner.split_once(':') {
+        Some(s) => s,
+        None => return false,
+    };
+    let scheme_ok = (2..=32).contains(&scheme.len())
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '.' || c == '-');
+    scheme_ok
+        && rest
+            .chars()
+            .all(|c| !c.is_ascii_control() && c != ' ' && c != '<' && c != '>')
+}
+
+/// 尖括号内是否为邮件自动链接
+pub fn is_email_autolink(inner: &str) -> bool {
+    let (local, domain) = match inner.split_once('@') {
+        Some(s) => s,
+        None => return false,
+    };
+    let local_ok = !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ".!#$%&'*+/=?^_`{|}~-".contains(c));
+    let domain_ok = domain.split('.').all(|label| {
+        (1..=63).contains(&label.len())
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+    });
+    local_ok && domain_ok
+}
+
+/// 逐字符识别 html 标签的游标
+struct Cursor<'s> {
+    src: &'s str,
+    pos: usize,
+}
+
+impl<'s> Cursor<'s> {
+    fn new(src: &'s str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> Option<()> {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn eat_while<F: Fn(char) -> bool>(&mut self, f: F) -> usize {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !f(c) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        self.pos - start
+    }
+
+    fn spaces(&mut self) -> bool {
+        self.eat_while(|c| c == ' ' || c == '\t' || c == '\n') > 0
+    }
+
+    fn tag_name(&mut self) -> Option<&'s str> {
+        let start = self.pos;
+        if !self.peek()?.is_ascii_alphabetic() {
+            return None;
+        }
+        self.eat_while(|c| c.is_ascii_alphanumeric() || c == '-');
+        Some(&self.src[start..self.pos])
+    }
+
+    fn attribute(&mut self) -> Option<(String, Option<String>)> {
+        let start = self.pos;
+        let first = self.peek()?;
+        if !(first.is_ascii_alphabetic() || first == '_' || first == ':') {
+            return None;
+        }
+        self.eat_while(|c| c.is_ascii_alphanumeric() || "_.:-".contains(c));
+        let name = self.src[start..self.pos].to_ascii_lowercase();
+        // 属性值
+        let before = self.pos;
+        self.spaces();
+        if self.eat('=').is_none() {
+            self.pos = before;
+            return Some((name, None));
+        }
+        self.spaces();
+        let value = match self.peek()? {
+            q @ ('"' | '\'') => {
+                self.pos += 1;
+                let start = self.pos;
+                self.eat_while(|c| c != q);
+                let value = &self.src[start..self.pos];
+                self.eat(q)?;
+                value
+            }
+            _ => {
+                let start = self.pos;
+                let len = self.eat_while(|c| !c.is_whitespace() && !"\"'=<>`".contains(c));
+                if len == 0 {
+                    return None;
+                }
+                &self.src[start..self.pos]
+            }
+        };
+        Some((name, Some(value.to_string())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_block_start() {
+        assert_eq!(HtmlBlockKind::start("<script>"), Some(HtmlBlockKind::Raw));
+        assert_eq!(
+            HtmlBlockKind::start("<PRE class=\"x\">"),
+            Some(HtmlBlockKind::Raw)
+        );
+        assert_eq!(
+            HtmlBlockKind::start("<!-- note"),
+            Some(HtmlBlockKind::Comment)
+        );
+        assert_eq!(
+            HtmlBlockKind::start("<?php"),
+            Some(HtmlBlockKind::Instruction)
+        );
+        assert_eq!(
+            HtmlBlockKind::start("<!DOCTYPE html>"),
+            Some(HtmlBlockKind::Declaration)
+        );
+        assert_eq!(
+            HtmlBlockKind::start("<![CDATA["),
+            Some(HtmlBlockKind::CData)
+        );
+        assert_eq!(HtmlBlockKind::start("<div>"), Some(HtmlBlockKind::Block));
+        assert_eq!(HtmlBlockKind::start("</table>"), Some(HtmlBlockKind::Block));
+        assert_eq!(
+            HtmlBlockKind::start("<custom-tag a=\"1\">"),
+            Some(HtmlBlockKind::Tag)
+        );
+        assert_eq!(HtmlBlockKind::start("<custom-tag> text"), None);
+        assert_eq!(HtmlBlockKind::start("<divider>"), Some(HtmlBlockKind::Tag));
+        assert_eq!(HtmlBlockKind::start("<http://a.b>"), None);
+    }
+
+    #[test]
+    fn test_inline_html() {
+        assert!(is_inline_html("<a href=\"x\">"));
+        assert!(is_inline_html("<br/>"));
+        assert!(is_inline_html("<img src=a.png alt='b' />"));
+        assert!(is_inline_html("</em >"));
+        assert!(is_inline_html("<!-- comment -->"));
+        assert!(is_inline_html("<?php echo 1; ?>"));
+        assert!(is_inline_html("<!DOCTYPE html>"));
+        assert!(!is_inline_html("<a href=>"));
+        assert!(!is_inline_html("<33>"));
+        assert!(!is_inline_html("<a b=\"c>"));
+    }
+
+    #[test]
+    fn test_parse_tag() {
+        let (tag, len) = parse_tag("<IMG src=a.png alt='b c' hidden /> tail").unwrap();
+        assert_eq!(len, 34);
+        assert_eq!(tag.name, "img");
+        assert!(tag.self_closing);
+        assert_eq!(
+            tag.attrs,
+            vec![
+                (String::from("src"), Some(String::from("a.png"))),
+                (String::from("alt"), Some(String::from("b c"))),
+                (String::from("hidden"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_autolink() {
+        assert!(is_uri_autolink("https://example.com/a?b=c"));
+        assert!(is_uri_autolink("irc://foo.bar:2233/baz"));
+        assert!(!is_uri_autolink("a:b"));
+        assert!(!is_uri_autolink("https://a b"));
+        assert!(is_email_autolink("foo@bar.example.com"));
+        assert!(!is_email_autolink("foo@bar-.com"));
+        assert!(!is_email_autolink("foo bar@baz.com"));
+    }
+}
@@ -0,0 +1,881 @@
+//! 行内语法分析
+//!
+//! 将词法单元转换为行内节点，强调使用分隔符栈处理
+
+use crate::lexical::{
+    token_trait::{FullToken, TokenKind},
+    tokens::{
+        angle::Angle,
+        code_snippet::CodeSnippet,
+        entity::{self, Entity},
+        math::Math,
+        wiki::Wiki,
+        FullTrans,
+    },
+    LexicalLoader, Token,
+};
+
+use super::{
+    ast::{Node, NodeKind},
+    attributes::Attributes,
+    html,
+    options::Options,
+};
+
+/// 行内分析的中间结果
+enum Item {
+    Node(Node),
+    Text(String),
+    Delim(Delim),
+    // `[` 或 `![`
+    Bracket { image: bool, active: bool },
+}
+
+/// 分隔符序列，如 `**` `_`
+struct Delim {
+    ch: char,
+    count: usize,
+    // 原始长度，用于 "3的倍数" 规则
+    origin: usize,
+    can_open: bool,
+    can_close: bool,
+}
+
+pub(super) fn parse_inline(text: &str, opts: &Options) -> Vec<Node> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut tokens = LexicalLoader::new(text.chars()).collect::<Vec<_>>();
+    let mut items = Vec::with_capacity(tokens.len());
+    // 当前词法单元在 chars 中的位置
+    let mut offset = 0;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let raw = tokens[i].1.clone();
+        let len = raw.chars().count();
+
+        match tokens[i].0.kind() {
+            TokenKind::CodeSnippet => {
+                let literal = downcast::<CodeSnippet>(&tokens[i]).inner.clone();
+                items.push(Item::Node(Node::new(NodeKind::Code { literal })));
+            }
+            TokenKind::Math => {
+                let Math { inner, display } = downcast(&tokens[i]);
+                items.push(Item::Node(Node::new(NodeKind::Math {
+                    literal: inner.clone(),
+                    display: *display,
+                })));
+            }
+            TokenKind::Trans => {
+                let ch = downcast::<FullTrans>(&tokens[i]).ch;
+                items.push(Item::Text(ch.to_string()));
+            }
+            TokenKind::LinkStart => items.push(Item::Bracket {
+                image: false,
+                active: true,
+            }),
+            TokenKind::ImgStart => items.push(Item::Bracket {
+                image: true,
+                active: true,
+            }),
+            TokenKind::BoxMid => {
+                if let Some((used, used_len)) = close_link(&mut items, &tokens[i + 1..]) {
+                    i += used;
+                    offset += used_len;
+                    offset += link_attrs(&mut items, &mut tokens, i + 1, &chars, offset + len);
+                } else {
+                    items.push(Item::Text(raw));
+                }
+            }
+            TokenKind::Wiki => {
+                items.push(Item::Node(wiki_link(downcast(&tokens[i]), opts)));
+                offset += link_attrs(&mut items, &mut tokens, i + 1, &chars, offset + len);
+            }
+            TokenKind::Plain if raw.starts_with("[[") || raw.starts_with("![[") => {
+                // 未闭合的维基链接，`[` 或 `![` 作为括号，之后的全部输入重新分析，
+                // 使后面的 `](` 仍可闭合普通链接
+                let image = raw.starts_with('!');
+                let skip = if image { 2 } else { 1 };
+                items.push(Item::Bracket {
+                    image,
+                    active: true,
+                });
+                let rest = String::from_iter(&chars[offset + skip..]);
+                tokens.truncate(i + 1);
+                tokens.extend(LexicalLoader::new(rest.chars()));
+                offset += skip;
+                i += 1;
+                continue;
+            }
+            TokenKind::Angle => {
+                let inner = downcast::<Angle>(&tokens[i]).inner.clone();
+                if html::is_uri_autolink(&inner) {
+                    items.push(Item::Node(autolink(inner.clone(), inner)));
+                } else if html::is_email_autolink(&inner) {
+                    items.push(Item::Node(autolink(format!("mailto:{}", inner), inner)));
+                } else if html::is_inline_html(&raw) {
+                    items.push(Item::Node(Node::new(NodeKind::HtmlInline { literal: raw })));
+                } else {
+                    // 不是 html 也不是链接，`<` 作为普通文本，其余部分重新分析
+                    items.push(Item::Text(String::from("<")));
+                    let relex = LexicalLoader::new(raw[1..].chars()).collect::<Vec<_>>();
+                    tokens.splice(i + 1..i + 1, relex);
+                    offset += 1;
+                    i += 1;
+                    continue;
+                }
+            }
+            TokenKind::Entity => {
+                let value = downcast::<Entity>(&tokens[i]).value.clone();
+                if value.is_empty() {
+                    push_text(&mut items, &raw, &chars, offset, opts);
+                } else {
+                    items.push(Item::Node(Node::new(NodeKind::Entity {
+                        literal: raw,
+                        value,
+                    })));
+                }
+            }
+            TokenKind::HardBreak => items.push(Item::Node(Node::new(NodeKind::HardBreak))),
+            TokenKind::ChangeLine | TokenKind::NewParam => soft_break(&mut items),
+            _ if !raw.is_empty() && raw.chars().all(|c| c == '*') => {
+                // 连续的 `*` 合并为一个分隔符序列
+                let mut count = len;
+                while let Some(Token(_, next)) = tokens.get(i + 1) {
+                    if next.is_empty() || !next.chars().all(|c| c == '*') {
+                        break;
+                    }
+                    count += next.chars().count();
+                    i += 1;
+                }
+                items.push(Item::Delim(Delim::new('*', &chars, offset, offset + count)));
+                offset += count;
+                i += 1;
+                continue;
+            }
+            TokenKind::UnorderList => {
+                // 行首的列表标记在段落内是普通文本，与之后的文本合并以识别 `++`
+                let mut text = raw;
+                if tokens
+                    .get(i + 1)
+                    .is_some_and(|t| t.0.kind() == TokenKind::Plain)
+                {
+                    text.push_str(&tokens[i + 1].1);
+                    i += 1;
+                }
+                push_text(&mut items, &text, &chars, offset, opts);
+                offset += text.chars().count();
+                i += 1;
+                continue;
+            }
+            _ => push_text(&mut items, &raw, &chars, offset, opts),
+        }
+        offset += len;
+        i += 1;
+    }
+
+    process_emphasis(&mut items, 0);
+    into_nodes(items)
+}
+
+/// 依据 `kind()` 确定类型后借用具体的 token
+fn downcast<T: FullToken + 'static>(token: &Token) -> &T {
+    token
+        .0
+        .downcast_ref()
+        .expect("token kind does not match its type")
+}
+
+fn autolink(destination: String, text: String) -> Node {
+    Node::with_children(
+        NodeKind::Link {
+            destination,
+            title: String::new(),
+        },
+        vec![Node::text(text)],
+    )
+}
+
+/// 维基链接，未给出 `|` 标签时以目标作为文本
+fn wiki_link(token: &Wiki, opts: &Options) -> Node {
+    let target = token.target.clone();
+    let fragment = token.fragment.clone();
+    let text = match (token.label.is_empty(), fragment.is_empty()) {
+        (false, _) => token.label.clone(),
+        (true, true) => target.clone(),
+        (true, false) => format!("{}#{}", target, fragment),
+    };
+    let destination = opts.resolve_wiki(&target, &fragment);
+    let kind = NodeKind::WikiLink {
+        target,
+        fragment,
+        destination,
+        embed: token.embed,
+    };
+    Node::with_children(kind, vec![Node::text(text)])
+}
+
+/// 软换行，之前的空格不输出
+fn soft_break(items: &mut Vec<Item>) {
+    if let Some(Item::Text(last)) = items.last_mut() {
+        last.truncate(last.trim_end_matches(' ').len());
+    }
+    items.push(Item::Node(Node::new(NodeKind::SoftBreak)));
+}
+
+/// 普通文本，`_` 序列与开启的扩展分隔符拆分为分隔符
+///
+/// 未能识别而退回为普通文本的词法单元可能跨行，其中的换行同样是软换行
+fn push_text(items: &mut Vec<Item>, raw: &str, chars: &[char], offset: usize, opts: &Options) {
+    let raw = raw.chars().collect::<Vec<_>>();
+    let mut start = 0;
+    let mut idx = 0;
+    while idx < raw.len() {
+        let ch = raw[idx];
+        if ch == '\n' {
+            if start < idx {
+                items.push(Item::Text(raw[start..idx].iter().collect()));
+            }
+            soft_break(items);
+            idx += 1;
+            start = idx;
+            continue;
+        }
+        let end = idx + raw[idx..].iter().take_while(|c| **c == ch).count();
+        if !(ch == '_' || is_extension(ch, end - idx, opts)) {
+            idx = end;
+            continue;
+        }
+        if start < idx {
+            items.push(Item::Text(raw[start..idx].iter().collect()));
+        }
+        items.push(Item::Delim(Delim::new(
+            ch,
+            chars,
+            offset + idx,
+            offset + end,
+        )));
+        start = end;
+        idx = end;
+    }
+    if start < raw.len() {
+        items.push(Item::Text(raw[start..].iter().collect()));
+    }
+}
+
+/// 扩展分隔符，序列长度必须与语法一致
+///
+/// `~` 单个为下标，两个为删除线，两者可以同时开启
+fn is_extension(ch: char, len: usize, opts: &Options) -> bool {
+    match (ch, len) {
+        ('=', 2) => opts.mark,
+        ('+', 2) => opts.insert,
+        ('^', 1) => opts.superscript,
+        ('~', 1) => opts.subscript,
+        ('~', 2) => opts.strikethrough,
+        _ => false,
+    }
+}
+
+/// 按 `order` 的顺序查找，未转义的 `ch` 是否出现在空白之前
+fn closed_before_space(chars: &[char], ch: char, mut order: impl Iterator<Item = usize>) -> bool {
+    order
+        .find_map(|i| match chars[i] {
+            c if c == ch && !(i > 0 && chars[i - 1] == '\\') => Some(true),
+            c if c.is_whitespace() => Some(false),
+            _ => None,
+        })
+        .unwrap_or(false)
+}
+
+impl Delim {
+    fn is_emphasis(&self) -> bool {
+        matches!(self.ch, '*' | '_')
+    }
+
+    /// 根据前后字符判断左右侧翼
+    fn new(ch: char, chars: &[char], start: usize, end: usize) -> Self {
+        let before = start.checked_sub(1).and_then(|i| chars.get(i)).copied();
+        let after = chars.get(end).copied();
+        let space = |c: Option<char>| c.map(char::is_whitespace).unwrap_or(true);
+        let punct = |c: Option<char>| {
+            c.map(|c| c.is_ascii_punctuation() || !(c.is_alphanumeric() || c.is_whitespace()))
+                .unwrap_or(false)
+        };
+
+        let left = !space(after) && (!punct(after) || space(before) || punct(before));
+        let right = !space(before) && (!punct(before) || space(after) || punct(after));
+        let (can_open, can_close) = if ch == '_' {
+            (
+                left && (!right || punct(before)),
+                right && (!left || punct(after)),
+            )
+        } else if matches!(ch, '~' | '^') && end - start == 1 {
+            // 下标与上标的内容不能包含空白
+            (
+                left && closed_before_space(&chars[end..], ch, 0..chars.len() - end),
+                right && closed_before_space(&chars[..start], ch, (0..start).rev()),
+            )
+        } else {
+            (left, right)
+        };
+        Self {
+            ch,
+            count: end - start,
+            origin: end - start,
+            can_open,
+            can_close,
+        }
+    }
+}
+
+/// 遇到 `](` 时尝试闭合最近的 `[`，成功时返回消耗的词法单元数量与字符数量
+fn close_link(items: &mut Vec<Item>, rest: &[Token]) -> Option<(usize, usize)> {
+    let opener = items
+        .iter()
+        .rposition(|item| matches!(item, Item::Bracket { .. }))?;
+    let image = match items[opener] {
+        Item::Bracket { active: false, .. } => {
+            items[opener] = Item::Text(String::from("["));
+            return None;
+        }
+        Item::Bracket { image, .. } => image,
+        _ => unreachable!(),
+    };
+
+    // 查找能够构成合法链接目标的 `)`
+    let mut tail = String::new();
+    let mut found = None;
+    for (idx, Token(token, raw)) in rest.iter().enumerate() {
+        if token.kind() == TokenKind::BoxEnd {
+            if let Some(res) = parse_link_tail(&tail) {
+                found = Some((idx + 1, res));
+                break;
+            }
+        }
+        tail.push_str(raw);
+    }
+    let (used, (destination, title)) = match found {
+        Some(f) => f,
+        None => {
+            items[opener] = Item::Text(String::from(if image { "![" } else { "[" }));
+            return None;
+        }
+    };
+    let used_len = rest[..used]
+        .iter()
+        .map(|Token(_, raw)| raw.chars().count())
+        .sum();
+
+    process_emphasis(items, opener + 1);
+    let children = into_nodes(items.drain(opener + 1..));
+    items.pop();
+    let kind = if image {
+        NodeKind::Image { destination, title }
+    } else {
+        // 链接内不能再包含链接
+        for item in items.iter_mut() {
+            if let Item::Bracket {
+                image: false,
+                active,
+            } = item
+            {
+                *active = false;
+            }
+        }
+        NodeKind::Link { destination, title }
+    };
+    items.push(Item::Node(Node::with_children(kind, children)));
+    Some((used, used_len))
+}
+
+/// 紧跟在链接之后的属性块，附加到链接节点上并移除对应的词法单元
+///
+/// `next` 与 `offset` 为链接之后的词法单元序号与字符位置，返回消耗的字符数
+fn link_attrs(
+    items: &mut [Item],
+    tokens: &mut Vec<Token>,
+    next: usize,
+    chars: &[char],
+    offset: usize,
+) -> usize {
+    if chars.get(offset) != Some(&'{') {
+        return 0;
+    }
+    let end = match chars[offset..].iter().position(|c| matches!(c, '}' | '\n')) {
+        Some(end) if chars[offset + end] == '}' => end + 1,
+        _ => return 0,
+    };
+    let attrs = match Attributes::parse(&String::from_iter(&chars[offset..offset + end])) {
+        Some(attrs) => attrs,
+        None => return 0,
+    };
+    if let Some(Item::Node(node)) = items.last_mut() {
+        node.attrs = attrs;
+    }
+
+    // 移除属性块占用的词法单元，跨越边界的单元重新分析剩余部分
+    let mut rest = end;
+    let mut idx = next;
+    while rest > 0 && idx < tokens.len() {
+        let len = tokens[idx].1.chars().count();
+        if len <= rest {
+            rest -= len;
+            idx += 1;
+        } else {
+            let tail = tokens[idx].1.chars().skip(rest).collect::<String>();
+            let relex = LexicalLoader::new(tail.chars()).collect::<Vec<_>>();
+            tokens.splice(idx..idx + 1, relex);
+            rest = 0;
+        }
+    }
+    tokens.drain(next..idx);
+    end
+}
+
+/// 解析 `(` 与 `)` 之间的链接目标与标题
+fn parse_link_tail(tail: &str) -> Option<(String, String)> {
+    let tail = tail.trim();
+    let (destination, rest) = if let Some(inner) = tail.strip_prefix('<') {
+        let end = unescaped(inner)
+            .find(|(_, c)| matches!(c, '>' | '<' | '\n'))?
+            .0;
+        if &inner[end..end + 1] != ">" {
+            return None;
+        }
+        (&inner[..end], &inner[end + 1..])
+    } else {
+        // 括号必须平衡
+        let mut depth = 0usize;
+        let mut end = tail.len();
+        for (idx, c) in unescaped(tail) {
+            match c {
+                '(' => depth += 1,
+                ')' if depth == 0 => return None,
+                ')' => depth -= 1,
+                c if c.is_whitespace() || c.is_ascii_control() => {
+                    end = idx;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        if depth != 0 {
+            return None;
+        }
+        (&tail[..end], &tail[end..])
+    };
+
+    // 标题与目标之间必须有空白
+    let separated = rest.starts_with(char::is_whitespace);
+    let rest = rest.trim();
+    let title = if rest.is_empty() {
+        ""
+    } else {
+        if rest.len() < 2 || !separated {
+            return None;
+        }
+        let close = match rest.chars().next()? {
+            '"' => '"',
+            '\'' => '\'',
+            '(' => ')',
+            _ => return None,
+        };
+        if !rest.ends_with(close) {
+            return None;
+        }
+        &rest[1..rest.len() - 1]
+    };
+    Some((unescape(destination), unescape(title)))
+}
+
+/// 跳过被反斜杠转义的字符
+fn unescaped(src: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut escaped = false;
+    src.char_indices().filter(move |(_, c)| {
+        let skip = escaped;
+        escaped = !escaped && *c == '\\';
+        !skip && !escaped
+    })
+}
+
+/// 处理链接目标与标题中的转义与字符引用
+fn unescape(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut plain = String::new();
+    let mut chars = src.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(&next) if c == '\\' && next.is_ascii_punctuation() => {
+                out.push_str(&entity::decode_all(&plain));
+                plain.clear();
+                out.push(next);
+                chars.next();
+            }
+            _ => plain.push(c),
+        }
+    }
+    out.push_str(&entity::decode_all(&plain));
+    out
+}
+
+/// 处理 `bottom` 之后的分隔符，生成强调节点
+fn process_emphasis(items: &mut Vec<Item>, bottom: usize) {
+    let mut closer = bottom;
+    while closer < items.len() {
+        let (ch, origin, closer_both, emphasis) = match &items[closer] {
+            Item::Delim(d) if d.can_close && d.count > 0 => {
+                (d.ch, d.origin, d.can_open, d.is_emphasis())
+            }
+            _ => {
+                closer += 1;
+                continue;
+            }
+        };
+        let opener = (bottom..closer).rev().find(|&o| match &items[o] {
+            // 扩展分隔符只与等长的序列匹配
+            Item::Delim(d) if d.ch == ch && d.can_open && d.count > 0 && !emphasis => {
+                d.origin == origin
+            }
+            Item::Delim(d) if d.ch == ch && d.can_open && d.count > 0 => {
+                // 3的倍数规则
+                !((d.can_close || closer_both)
+                    && (d.origin + origin) % 3 == 0
+                    && !(d.origin % 3 == 0 && origin % 3 == 0))
+            }
+            _ => false,
+        });
+        let opener = match opener {
+            Some(o) => o,
+            None => {
+                closer += 1;
+                continue;
+            }
+        };
+
+        let use_count = match (&items[opener], &items[closer]) {
+            _ if !emphasis => origin,
+            (Item::Delim(o), Item::Delim(c)) if o.count >= 2 && c.count >= 2 => 2,
+            _ => 1,
+        };
+        for idx in [opener, closer] {
+            if let Item::Delim(d) = &mut items[idx] {
+                d.count -= use_count;
+            }
+        }
+        let kind = match (ch, use_count) {
+            ('=', _) => NodeKind::Mark,
+            ('+', _) => NodeKind::Insert,
+            ('^', _) => NodeKind::Superscript,
+            ('~', 1) => NodeKind::Subscript,
+            ('~', _) => NodeKind::Strikethrough,
+            (_, 2) => NodeKind::Strong,
+            _ => NodeKind::Emphasis,
+        };
+        let children = into_nodes(items.drain(opener + 1..closer));
+        items.insert(opener + 1, Item::Node(Node::with_children(kind, children)));
+        closer = opener + 2;
+
+        if matches!(&items[opener], Item::Delim(d) if d.count == 0) {
+            items.remove(opener);
+            closer -= 1;
+        }
+        if matches!(&items[closer], Item::Delim(d) if d.count == 0) {
+            items.remove(closer);
+        }
+    }
+}
+
+/// 中间结果转换为节点，合并相邻文本
+fn into_nodes<I: IntoIterator<Item = Item>>(items: I) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    for item in items {
+        let node = match item {
+            Item::Node(n) => n,
+            Item::Text(s) => Node::text(s),
+            Item::Delim(d) => Node::text(d.ch.to_string().repeat(d.count)),
+            Item::Bracket { image: true, .. } => Node::text("!["),
+            Item::Bracket { image: false, .. } => Node::text("["),
+        };
+        if let NodeKind::Text { value } = &node.kind {
+            if value.is_empty() {
+                continue;
+            }
+            if let Some(Node {
+                kind: NodeKind::Text { value: last },
+                ..
+            }) = nodes.last_mut()
+            {
+                last.push_str(value);
+                continue;
+            }
+        }
+        nodes.push(node);
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse_inline(text: &str) -> Vec<Node> {
+        super::parse_inline(text, &Options::default())
+    }
+
+    fn kinds(nodes: &[Node]) -> Vec<&NodeKind> {
+        nodes.iter().map(|n| &n.kind).collect()
+    }
+
+    #[test]
+    fn test_emphasis() {
+        let nodes = parse_inline("a *b* **c** ***d***");
+        assert_eq!(nodes[1].kind, NodeKind::Emphasis);
+        assert_eq!(nodes[1].text_content(), "b");
+        assert_eq!(nodes[3].kind, NodeKind::Strong);
+        assert_eq!(nodes[3].text_content(), "c");
+        assert_eq!(nodes[5].text_content(), "d");
+
+        let nodes = parse_inline("snake_case_name and _em_");
+        assert_eq!(nodes[0], Node::text("snake_case_name and "));
+        assert_eq!(nodes[1].kind, NodeKind::Emphasis);
+    }
+
+    #[test]
+    fn test_link() {
+        let nodes = parse_inline("see [the *docs*](http://a.b/c_(d) \"T\") now");
+        assert_eq!(
+            nodes[1].kind,
+            NodeKind::Link {
+                destination: String::from("http://a.b/c_(d)"),
+                title: String::from("T")
+            }
+        );
+        assert_eq!(nodes[1].children[1].kind, NodeKind::Emphasis);
+        assert_eq!(nodes[2], Node::text(" now"));
+
+        let nodes = parse_inline("![img](a.png)");
+        assert_eq!(
+            kinds(&nodes),
+            vec![&NodeKind::Image {
+                destination: String::from("a.png"),
+                title: String::new()
+            }]
+        );
+
+        let nodes = parse_inline("[not](a link");
+        assert_eq!(nodes, vec![Node::text("[not](a link")]);
+    }
+
+    #[test]
+    fn test_html_inline() {
+        let nodes = parse_inline("a <span class=\"x\">b</span> <!-- c > d --> e");
+        assert_eq!(
+            nodes[1].kind,
+            NodeKind::HtmlInline {
+                literal: String::from("<span class=\"x\">")
+            }
+        );
+        assert_eq!(nodes[2], Node::text("b"));
+        assert_eq!(
+            nodes[3].kind,
+            NodeKind::HtmlInline {
+                literal: String::from("</span>")
+            }
+        );
+        assert_eq!(
+            nodes[5].kind,
+            NodeKind::HtmlInline {
+                literal: String::from("<!-- c > d -->")
+            }
+        );
+    }
+
+    #[test]
+    fn test_autolink() {
+        let nodes = parse_inline("<https://example.com> <me@example.com>");
+        assert_eq!(
+            nodes[0],
+            autolink(
+                String::from("https://example.com"),
+                String::from("https://example.com")
+            )
+        );
+        assert_eq!(
+            nodes[2],
+            autolink(
+                String::from("mailto:me@example.com"),
+                String::from("me@example.com")
+            )
+        );
+    }
+
+    #[test]
+    fn test_entity() {
+        let nodes = parse_inline("&copy; &#x4E2D;&#35; &nosuch; & x");
+        assert_eq!(
+            nodes[0].kind,
+            NodeKind::Entity {
+                literal: String::from("&copy;"),
+                value: String::from("\u{A9}")
+            }
+        );
+        assert_eq!(nodes[2].text_content(), "中");
+        assert_eq!(nodes[3].text_content(), "#");
+        assert_eq!(nodes[4], Node::text(" &nosuch; & x"));
+    }
+
+    #[test]
+    fn test_escape() {
+        let nodes = parse_inline("\\*a\\* \\_b\\_ \\<br> \\a \\~\\\"\\:\\= `\\*`");
+        assert_eq!(nodes[0], Node::text("*a* _b_ <br> \\a ~\":= "));
+        assert_eq!(
+            nodes[1].kind,
+            NodeKind::Code {
+                literal: String::from("\\*")
+            }
+        );
+
+        let nodes = parse_inline("<http://a.b/\\_x> [l](/a\\)b \"\\\"&amp;\\&amp;\")");
+        assert_eq!(nodes[0].text_content(), "http://a.b/\\_x");
+        assert_eq!(
+            nodes[2].kind,
+            NodeKind::Link {
+                destination: String::from("/a)b"),
+                title: String::from("\"&&amp;")
+            }
+        );
+    }
+
+    #[test]
+    fn test_math() {
+        let nodes = parse_inline("costs $5 or $6, $x_1 * y_2$ and $$\\frac{a}{b}$$");
+        assert_eq!(nodes[0], Node::text("costs $5 or $6, "));
+        assert_eq!(
+            nodes[1].kind,
+            NodeKind::Math {
+                literal: String::from("x_1 * y_2"),
+                display: false
+            }
+        );
+        assert_eq!(
+            nodes[3].kind,
+            NodeKind::Math {
+                literal: String::from("\\frac{a}{b}"),
+                display: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_not_html() {
+        let nodes = parse_inline("1 < 2 and a<b *c*> d");
+        assert_eq!(nodes[0], Node::text("1 < 2 and a<b "));
+        assert_eq!(nodes[1].kind, NodeKind::Emphasis);
+        assert_eq!(nodes[2], Node::text("> d"));
+    }
+
+    #[test]
+    fn test_plain_soft_break() {
+        // 未结束的注释退回为跨行的普通文本
+        let nodes = parse_inline("a <!--  \nb");
+        assert_eq!(nodes[0], Node::text("a <!--"));
+        assert_eq!(nodes[1].kind, NodeKind::SoftBreak);
+        assert_eq!(nodes[2], Node::text("b"));
+    }
+
+    #[test]
+    fn test_wiki_link() {
+        let nodes = parse_inline("[[Page#Intro]] and [[a|b]] ![[img.png]]");
+        assert_eq!(
+            nodes[0].kind,
+            NodeKind::WikiLink {
+                target: String::from("Page"),
+                fragment: String::from("Intro"),
+                destination: Some(String::from("Page#Intro")),
+                embed: false
+            }
+        );
+        assert_eq!(nodes[0].text_content(), "Page#Intro");
+        assert_eq!(nodes[2].text_content(), "b");
+        assert!(matches!(
+            nodes[4].kind,
+            NodeKind::WikiLink { embed: true, .. }
+        ));
+
+        // 未闭合时 `[` 仍可作为链接开始
+        let nodes = parse_inline("[[x] y](/u) ![[z");
+        assert_eq!(nodes[0], Node::text("["));
+        assert_eq!(nodes[1].text_content(), "x] y");
+        assert_eq!(nodes[2], Node::text(" ![[z"));
+        let nodes = parse_inline("[[x](y)");
+        assert_eq!(nodes[0], Node::text("["));
+        assert!(matches!(nodes[1].kind, NodeKind::Link { .. }));
+        assert_eq!(nodes[1].text_content(), "x");
+        for src in ["[[ ]]", "[[#]]"] {
+            assert_eq!(parse_inline(src), vec![Node::text(src)]);
+        }
+
+        let opts = Options::new().wiki_resolver(|_: &str, _: &str| None);
+        let nodes = super::parse_inline("[[missing]]", &opts);
+        assert!(matches!(
+            nodes[0].kind,
+            NodeKind::WikiLink {
+                destination: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_link_attributes() {
+        let nodes =
+            parse_inline("[a](/u){.btn target=_blank}b ![i](p.png){width=50} [[w]]{#w} {x}");
+        assert_eq!(nodes[0].attrs.classes, vec![String::from("btn")]);
+        assert_eq!(nodes[1], Node::text("b "));
+        assert_eq!(
+            nodes[2].attrs.pairs,
+            vec![(String::from("width"), String::from("50"))]
+        );
+        assert_eq!(nodes[4].attrs.id.as_deref(), Some("w"));
+        assert_eq!(nodes[5], Node::text(" {x}"));
+
+        // 转义或无法识别的花括号保持原样
+        let nodes = parse_inline("[a](/u)\\{.x} [b](/v){not attrs}");
+        assert_eq!(nodes[1], Node::text("{.x} "));
+        assert_eq!(nodes[3], Node::text("{not attrs}"));
+    }
+
+    #[test]
+    fn test_extensions() {
+        let opts = Options::new()
+            .subscript(true)
+            .strikethrough(true)
+            .insert(true);
+        let nodes = super::parse_inline("~~a~~ H~2~O ~~~x~~~ ~y~~", &opts);
+        assert_eq!(nodes[0].kind, NodeKind::Strikethrough);
+        assert_eq!(nodes[0].text_content(), "a");
+        assert_eq!(nodes[2].kind, NodeKind::Subscript);
+        assert_eq!(nodes[2].text_content(), "2");
+        // 长度不一致的序列不匹配
+        assert_eq!(nodes[3], Node::text("O ~~~x~~~ ~y~~"));
+
+        // 行首的 `+` 与之后的文本合并
+        let nodes = super::parse_inline("++new++ a + b\n++c++", &opts);
+        assert_eq!(nodes[0].kind, NodeKind::Insert);
+        assert_eq!(nodes[1], Node::text(" a + b"));
+        assert_eq!(nodes[3].kind, NodeKind::Insert);
+
+        // 下标与上标的内容不能包含空白
+        let opts = opts.superscript(true);
+        let nodes = super::parse_inline("~a b~ ^c d^ ~e\\~f~ x^2^", &opts);
+        assert_eq!(nodes[0], Node::text("~a b~ ^c d^ "));
+        assert_eq!(nodes[1].kind, NodeKind::Subscript);
+        assert_eq!(nodes[1].text_content(), "e~f");
+        assert_eq!(nodes[3].kind, NodeKind::Superscript);
+
+        let opts = Options::new().strikethrough(true);
+        let nodes = super::parse_inline("~a~ ~~b~~", &opts);
+        assert_eq!(nodes[0], Node::text("~a~ "));
+        assert_eq!(nodes[1].kind, NodeKind::Strikethrough);
+    }
+}
@@ -0,0 +1,218 @@
+//! 文档树的 JSON 导出
+//!
+//! 导出格式带有版本号，节点结构发生不兼容的变化时增加 [`VERSION`]。
+//! 位置只出现在根节点与顶层块上（[`JsonRoot`]、[`JsonBlock`]），为从 1 开始的行范围，不含列；
+//! 嵌套的块与行内节点（[`JsonNode`]）没有位置。
+//! 仓库中的 `schema/ast.schema.json` 由 [`schema`] 生成
+
+use std::ops::Range;
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Metadata, RootSchema, Schema, SchemaObject, SubschemaValidation},
+    schema_for, JsonSchema,
+};
+
+use super::{finish, parse_blocks, Attributes, Node, NodeKind, Options};
+
+/// 导出格式的版本
+pub const VERSION: u32 = 1;
+
+/// 导出的文档
+#[derive(Debug, Clone, PartialEq, serde::Serialize, JsonSchema)]
+#[schemars(title = "markdown_analyze AST")]
+pub struct JsonDocument {
+    /// 导出格式的版本
+    pub version: u32,
+    /// 类型为 `document` 的根节点
+    pub root: JsonRoot,
+}
+
+/// 根节点，子节点为带有位置的顶层块
+#[derive(Debug, Clone, PartialEq, serde::Serialize, JsonSchema)]
+pub struct JsonRoot {
+    #[serde(rename = "type")]
+    pub kind: RootType,
+    #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+    pub attributes: Attributes,
+    /// 整个文档占用的行，输入为空时没有位置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<JsonBlock>,
+}
+
+/// 根节点的类型，只有 `document`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RootType {
+    Document,
+}
+
+/// 顶层块，在 [`JsonNode`] 的基础上带有位置
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct JsonBlock {
+    #[serde(flatten)]
+    pub node: JsonNode,
+    /// 块在源码中占用的行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+}
+
+// 派生的 schema 会展开 `JsonNode` 的全部变体，这里引用 `JsonNode` 并增加 `position`
+impl JsonSchema for JsonBlock {
+    fn schema_name() -> String {
+        String::from("JsonBlock")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut position = SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            ..Default::default()
+        };
+        position
+            .object()
+            .properties
+            .insert(String::from("position"), gen.subschema_for::<Position>());
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(String::from("顶层块，`position` 为块在源码中占用的行")),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                all_of: Some(vec![gen.subschema_for::<JsonNode>(), position.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// 导出的节点，`type` 与节点数据平铺在同一对象中，不带位置
+#[derive(Debug, Clone, PartialEq, serde::Serialize, JsonSchema)]
+pub struct JsonNode {
+    #[serde(flatten)]
+    pub kind: NodeKind,
+    /// `{#id .class key=value}` 属性
+    #[serde(default, skip_serializing_if = "Attributes::is_empty")]
+    pub attributes: Attributes,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<JsonNode>,
+}
+
+/// 行范围，从 1 开始，包含 `end_line`，不含列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, JsonSchema)]
+pub struct Position {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl From<Range<usize>> for Position {
+    /// 从 0 开始、不含结尾的行范围
+    fn from(span: Range<usize>) -> Self {
+        Self {
+            start_line: span.start + 1,
+            end_line: span.end.max(span.start + 1),
+        }
+    }
+}
+
+impl From<&Node> for JsonNode {
+    fn from(node: &Node) -> Self {
+        Self {
+            kind: node.kind.clone(),
+            attributes: node.attrs.clone(),
+            children: node.children.iter().map(JsonNode::from).collect(),
+        }
+    }
+}
+
+impl JsonDocument {
+    /// 导出已解析的文档，不含位置，`root` 应为 `Document` 节点
+    pub fn new(root: &Node) -> Self {
+        Self {
+            version: VERSION,
+            root: JsonRoot {
+                kind: RootType::Document,
+                attributes: root.attrs.clone(),
+                position: None,
+                children: root
+                    .children
+                    .iter()
+                    .map(|child| JsonBlock {
+                        node: child.into(),
+                        position: None,
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// 解析并导出文档，根节点与顶层块带有位置
+pub fn export(input: &str, opts: &Options) -> JsonDocument {
+    let (children, spans): (Vec<_>, Vec<_>) = parse_blocks(input, opts).into_iter().unzip();
+    let doc = finish(Node::with_children(NodeKind::Document, children), opts);
+    let mut out = JsonDocument::new(&doc);
+    for (child, span) in out.root.children.iter_mut().zip(spans) {
+        child.position = Some(span.into());
+    }
+    let lines = input.lines().count();
+    if lines > 0 {
+        out.root.position = Some((0..lines).into());
+    }
+    out
+}
+
+/// 导出格式的 JSON Schema
+pub fn schema() -> RootSchema {
+    schema_for!(JsonDocument)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_export() {
+        let out = export("# A {#a}\n\nsome\ntext\n", &Options::default());
+        assert_eq!(
+            serde_json::to_value(&out).unwrap(),
+            json!({
+                "version": 1,
+                "root": {
+                    "type": "document",
+                    "position": { "start_line": 1, "end_line": 4 },
+                    "children": [
+                        {
+                            "type": "heading",
+                            "level": 1,
+                            "attributes": { "id": "a" },
+                            "position": { "start_line": 1, "end_line": 1 },
+                            "children": [{ "type": "text", "value": "A" }]
+                        },
+                        {
+                            "type": "paragraph",
+                            "position": { "start_line": 3, "end_line": 4 },
+                            "children": [
+                                { "type": "text", "value": "some" },
+                                { "type": "soft_break" },
+                                { "type": "text", "value": "text" }
+                            ]
+                        }
+                    ]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_schema() {
+        // 节点结构变化后需要重新生成：`markdown_analyze schema > schema/ast.schema.json`
+        let schema = serde_json::to_string_pretty(&schema()).unwrap() + "\n";
+        assert_eq!(schema, include_str!("../../schema/ast.schema.json"));
+    }
+}
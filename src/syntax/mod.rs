@@ -0,0 +1,66 @@
+//! 语法分析，将词法单元组织为文档树
+//!
+use std::ops::Range;
+
+mod abbr;
+pub mod ast;
+pub mod attributes;
+mod block;
+pub mod front_matter;
+pub(crate) mod html;
+mod inline;
+pub mod json;
+pub mod options;
+pub mod toc;
+pub mod wiki;
+
+pub use ast::{Node, NodeKind};
+pub use attributes::Attributes;
+pub use front_matter::{FrontMatterError, FrontMatterFormat};
+pub use options::Options;
+pub use toc::{Toc, TocEntry};
+pub use wiki::WikiResolver;
+
+pub(crate) use block::may_start_block;
+
+/// 解析 markdown 文本，返回文档根节点
+pub fn parse(input: &str) -> Node {
+    parse_with(input, &Options::default())
+}
+
+/// 解析顶层的块，返回块与其在输入中占用的行范围（从 0 开始）
+///
+/// 不处理缩写与目录占位符
+pub fn parse_blocks(input: &str, opts: &Options) -> Vec<(Node, Range<usize>)> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    if let Some((node, used)) = front_matter::extract(&lines) {
+        blocks.push((node, 0..used));
+        start = used;
+    }
+    blocks.extend(
+        block::parse_spans(&lines[start..], opts)
+            .into_iter()
+            .map(|(node, span)| (node, span.start + start..span.end + start)),
+    );
+    blocks
+}
+
+/// 按给定的选项解析 markdown 文本
+pub fn parse_with(input: &str, opts: &Options) -> Node {
+    let children = parse_blocks(input, opts)
+        .into_iter()
+        .map(|(node, _)| node)
+        .collect();
+    finish(Node::with_children(NodeKind::Document, children), opts)
+}
+
+/// 处理缩写与目录占位符，不改变顶层块的数量与顺序
+fn finish(mut doc: Node, opts: &Options) -> Node {
+    if opts.abbreviations {
+        abbr::apply(&mut doc);
+    }
+    opts.toc.expand(&mut doc);
+    doc
+}
@@ -0,0 +1,143 @@
+//! 语法分析选项
+
+use std::{collections::HashSet, panic::RefUnwindSafe, sync::Arc};
+
+use super::{toc::Toc, wiki::WikiResolver};
+
+/// 默认的提示块类型
+const DEFAULT_ADMONITIONS: [&str; 7] = [
+    "note",
+    "tip",
+    "important",
+    "warning",
+    "caution",
+    "info",
+    "danger",
+];
+
+/// 语法分析选项，扩展语法在这里开启与配置
+#[derive(Debug, Clone)]
+pub struct Options {
+    // 提示块类型，小写
+    admonitions: HashSet<String>,
+    wiki_resolver: Option<Arc<dyn WikiResolver + Send + Sync + RefUnwindSafe>>,
+    // 行内扩展 `==mark==` `++ins++` `^sup^` `~sub~` `~~strike~~`，默认关闭
+    pub(crate) mark: bool,
+    pub(crate) insert: bool,
+    pub(crate) superscript: bool,
+    pub(crate) subscript: bool,
+    pub(crate) strikethrough: bool,
+    // 缩写定义 `*[abbr]: title`，默认关闭
+    pub(crate) abbreviations: bool,
+    // 替换 `[TOC]` 占位符时使用的目录配置
+    pub(crate) toc: Toc,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            admonitions: DEFAULT_ADMONITIONS.iter().map(|k| k.to_string()).collect(),
+            wiki_resolver: None,
+            mark: false,
+            insert: false,
+            superscript: false,
+            subscript: false,
+            strikethrough: false,
+            abbreviations: false,
+            toc: Toc::default(),
+        }
+    }
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 替换全部提示块类型，不区分大小写
+    pub fn admonition_kinds<I, S>(mut self, kinds: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.admonitions = kinds
+            .into_iter()
+            .map(|k| k.as_ref().to_lowercase())
+            .collect();
+        self
+    }
+
+    /// 增加一个提示块类型
+    pub fn admonition_kind(mut self, kind: &str) -> Self {
+        self.admonitions.insert(kind.to_lowercase());
+        self
+    }
+
+    /// 设置维基链接的解析器，未设置时目标原样作为地址
+    ///
+    /// 解析器需要可以在线程间共享，使 `Options` 为 `Send + Sync`
+    pub fn wiki_resolver<R>(mut self, resolver: R) -> Self
+    where
+        R: WikiResolver + Send + Sync + RefUnwindSafe + 'static,
+    {
+        self.wiki_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// `==mark==` 高亮
+    pub fn mark(mut self, enable: bool) -> Self {
+        self.mark = enable;
+        self
+    }
+
+    /// `++ins++` 插入
+    pub fn insert(mut self, enable: bool) -> Self {
+        self.insert = enable;
+        self
+    }
+
+    /// `^sup^` 上标，内容不能包含空白
+    pub fn superscript(mut self, enable: bool) -> Self {
+        self.superscript = enable;
+        self
+    }
+
+    /// `~sub~` 下标，内容不能包含空白
+    pub fn subscript(mut self, enable: bool) -> Self {
+        self.subscript = enable;
+        self
+    }
+
+    /// `~~strike~~` 删除线
+    pub fn strikethrough(mut self, enable: bool) -> Self {
+        self.strikethrough = enable;
+        self
+    }
+
+    /// `*[HTML]: Hyper Text Markup Language` 缩写
+    pub fn abbreviations(mut self, enable: bool) -> Self {
+        self.abbreviations = enable;
+        self
+    }
+
+    /// 替换 `[TOC]` 与 `<!-- toc -->` 占位符时使用的目录配置
+    pub fn toc(mut self, toc: Toc) -> Self {
+        self.toc = toc;
+        self
+    }
+
+    /// 是否为允许的提示块类型，返回小写的类型名
+    pub(crate) fn admonition(&self, kind: &str) -> Option<String> {
+        let kind = kind.to_lowercase();
+        self.admonitions.contains(&kind).then_some(kind)
+    }
+
+    /// 解析维基链接，目标不存在时返回 `None`
+    pub(crate) fn resolve_wiki(&self, target: &str, fragment: &str) -> Option<String> {
+        match &self.wiki_resolver {
+            Some(resolver) => resolver.resolve(target, fragment),
+            None if fragment.is_empty() => Some(target.to_string()),
+            None => Some(format!("{}#{}", target, fragment)),
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! 目录生成
+//!
+//! 收集标题为嵌套的目录树，可输出为列表节点或 markdown 文本，
+//! 文档中的 `[TOC]` 与 `<!-- toc -->` 占位符由生成的目录替换
+
+use std::collections::HashMap;
+
+use super::ast::{Node, NodeKind};
+
+/// 目录项
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct TocEntry {
+    pub level: u8,
+    pub text: String,
+    /// 标题的锚点，`{#id}` 指定时使用该值，否则由文本生成
+    pub anchor: String,
+    pub children: Vec<TocEntry>,
+}
+
+/// 目录配置，默认包含全部级别的标题
+#[derive(Debug, Clone)]
+pub struct Toc {
+    min_level: u8,
+    max_level: u8,
+}
+
+impl Default for Toc {
+    fn default() -> Self {
+        Self {
+            min_level: 1,
+            max_level: 6,
+        }
+    }
+}
+
+impl Toc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 最小的标题级别
+    pub fn min_level(mut self, level: u8) -> Self {
+        self.min_level = level;
+        self
+    }
+
+    /// 最大的标题级别
+    pub fn max_level(mut self, level: u8) -> Self {
+        self.max_level = level;
+        self
+    }
+
+    /// 收集文档内的标题为目录树
+    ///
+    /// 没有 `{#id}` 的标题会设置由文本生成的 id，输出的文档中存在目录指向的锚点
+    pub fn collect(&self, doc: &mut Node) -> Vec<TocEntry> {
+        assign_ids(doc, &mut Slugger::default());
+        let flat = headings(doc)
+            .into_iter()
+            .filter_map(|heading| {
+                let level = match heading.kind {
+                    NodeKind::Heading { level } => level,
+                    _ => unreachable!(),
+                };
+                (self.min_level..=self.max_level)
+                    .contains(&level)
+                    .then(|| TocEntry {
+                        level,
+                        text: heading.text_content(),
+                        anchor: heading.attrs.id.clone().unwrap_or_default(),
+                        children: vec![],
+                    })
+            })
+            .collect();
+        nest(flat)
+    }
+
+    /// 目录列表节点，每项为指向锚点的链接
+    pub fn to_node(&self, entries: &[TocEntry]) -> Node {
+        let items = entries
+            .iter()
+            .map(|entry| {
+                let link = Node::with_children(
+                    NodeKind::Link {
+                        destination: format!("#{}", entry.anchor),
+                        title: String::new(),
+                    },
+                    vec![Node::text(entry.text.as_str())],
+                );
+                let mut children = vec![Node::with_children(NodeKind::Paragraph, vec![link])];
+                if !entry.children.is_empty() {
+                    children.push(self.to_node(&entry.children));
+                }
+                Node::with_children(NodeKind::ListItem, children)
+            })
+            .collect();
+        let kind = NodeKind::List {
+            marker: '-',
+            start: None,
+            tight: true,
+        };
+        Node::with_children(kind, items)
+    }
+
+    /// 目录的 markdown 文本，子级缩进两个空格
+    pub fn to_markdown(&self, entries: &[TocEntry]) -> String {
+        let mut out = String::new();
+        write_markdown(entries, 0, &mut out);
+        out
+    }
+
+    /// 为标题设置锚点，并以目录替换文档中的占位符
+    pub(super) fn expand(&self, doc: &mut Node) {
+        if !has_placeholder(doc) {
+            return;
+        }
+        let entries = self.collect(doc);
+        let mut list = self.to_node(&entries);
+        list.attrs.classes.push(String::from("toc"));
+        replace_placeholder(doc, &list);
+    }
+}
+
+fn headings(node: &Node) -> Vec<&Node> {
+    match node.kind {
+        NodeKind::Heading { .. } => vec![node],
+        _ => node.children.iter().flat_map(headings).collect(),
+    }
+}
+
+/// 按级别组织为树，级别更大的标题属于之前最近的级别更小的标题
+fn nest(flat: Vec<TocEntry>) -> Vec<TocEntry> {
+    let mut roots: Vec<TocEntry> = Vec::new();
+    let mut stack: Vec<TocEntry> = Vec::new();
+    for entry in flat {
+        while stack.last().is_some_and(|top| top.level >= entry.level) {
+            pop_into(&mut stack, &mut roots);
+        }
+        stack.push(entry);
+    }
+    while !stack.is_empty() {
+        pop_into(&mut stack, &mut roots);
+    }
+    roots
+}
+
+fn pop_into(stack: &mut Vec<TocEntry>, roots: &mut Vec<TocEntry>) {
+    if let Some(top) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(top),
+            None => roots.push(top),
+        }
+    }
+}
+
+fn write_markdown(entries: &[TocEntry], depth: usize, out: &mut String) {
+    for entry in entries {
+        out.push_str(&"  ".repeat(depth));
+        // 文本中的方括号需要转义
+        let text = entry
+            .text
+            .chars()
+            .flat_map(|c| match c {
+                '[' | ']' | '\\' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect::<String>();
+        out.push_str(&format!("- [{}](#{})\n", text, entry.anchor));
+        write_markdown(&entry.children, depth + 1, out);
+    }
+}
+
+fn is_placeholder(node: &Node) -> bool {
+    match &node.kind {
+        NodeKind::Paragraph => {
+            matches!(&node.children[..], [Node { kind: NodeKind::Text { value }, .. }]
+                if value.eq_ignore_ascii_case("[toc]"))
+        }
+        NodeKind::HtmlBlock { literal } => {
+            let inner = literal.trim();
+            inner
+                .strip_prefix("<!--")
+                .and_then(|c| c.strip_suffix("-->"))
+                .is_some_and(|c| c.trim().eq_ignore_ascii_case("toc"))
+        }
+        _ => false,
+    }
+}
+
+fn has_placeholder(node: &Node) -> bool {
+    is_placeholder(node) || node.children.iter().any(has_placeholder)
+}
+
+fn replace_placeholder(node: &mut Node, list: &Node) {
+    for child in node.children.iter_mut() {
+        if is_placeholder(child) {
+            *child = list.clone();
+        } else {
+            replace_placeholder(child, list);
+        }
+    }
+}
+
+fn assign_ids(node: &mut Node, slugger: &mut Slugger) {
+    if let NodeKind::Heading { .. } = node.kind {
+        if node.attrs.id.is_none() {
+            node.attrs.id = Some(slugger.slug(&node.text_content()));
+        }
+        return;
+    }
+    for child in node.children.iter_mut() {
+        assign_ids(child, slugger);
+    }
+}
+
+/// 生成不重复的锚点，重复的文本依次添加 `-1` `-2` 后缀
+#[derive(Debug, Default)]
+pub struct Slugger {
+    seen: HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub fn slug(&mut self, text: &str) -> String {
+        let base = slugify(text);
+        let count = self.seen.entry(base.clone()).or_insert(0);
+        let slug = if *count == 0 {
+            base
+        } else {
+            format!("{}-{}", base, count)
+        };
+        *count += 1;
+        slug
+    }
+}
+
+/// GitHub 风格的锚点：转为小写，去除标点，空格替换为 `-`
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{render::html::HtmlRenderer, syntax::parse};
+
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World! 2.0"), "hello-world-20");
+        assert_eq!(slugify("中文 标题_x"), "中文-标题_x");
+        let mut slugger = Slugger::default();
+        assert_eq!(slugger.slug("A"), "a");
+        assert_eq!(slugger.slug("a"), "a-1");
+        assert_eq!(slugger.slug("A"), "a-2");
+    }
+
+    #[test]
+    fn test_collect() {
+        let mut doc = parse("# Title\n## One\n### Deep\n## Two {#second}\n# Title\n#### Skip");
+        let entries = Toc::new().max_level(3).collect(&mut doc);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].children.len(), 2);
+        assert_eq!(entries[0].children[0].children[0].anchor, "deep");
+        assert_eq!(entries[0].children[1].anchor, "second");
+        assert_eq!(entries[1].anchor, "title-1");
+        assert_eq!(
+            Toc::new()
+                .min_level(2)
+                .to_markdown(&Toc::new().min_level(2).collect(&mut doc)),
+            "- [One](#one)\n  - [Deep](#deep)\n- [Two](#second)\n  - [Skip](#skip)\n"
+        );
+    }
+
+    #[test]
+    fn test_anchors_resolve() {
+        // 没有占位符时目录的锚点同样存在于输出中
+        let mut doc = parse("# A\n## B {#b2}\n# A\n## `c` d");
+        let entries = Toc::new().collect(&mut doc);
+        let html = HtmlRenderer::new().render(&doc);
+        let mut anchors = Vec::new();
+        let mut stack = entries.iter().collect::<Vec<_>>();
+        while let Some(entry) = stack.pop() {
+            anchors.push(entry.anchor.as_str());
+            stack.extend(&entry.children);
+        }
+        anchors.sort_unstable();
+        assert_eq!(anchors, vec!["a", "a-1", "b2", "c-d"]);
+        for anchor in anchors {
+            assert!(html.contains(&format!("id=\"{}\"", anchor)), "{}", html);
+        }
+    }
+}
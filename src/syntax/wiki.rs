@@ -0,0 +1,26 @@
+//! 维基链接 `[[target]]` 的目标解析
+
+use std::{fmt, panic::RefUnwindSafe};
+
+/// 将维基链接的目标解析为地址
+///
+/// 闭包 `Fn(&str, &str) -> Option<String>` 可直接作为解析器使用
+pub trait WikiResolver {
+    /// `fragment` 为 `#` 之后的部分，没有时为空；目标不存在时返回 `None`
+    fn resolve(&self, target: &str, fragment: &str) -> Option<String>;
+}
+
+impl<F> WikiResolver for F
+where
+    F: Fn(&str, &str) -> Option<String>,
+{
+    fn resolve(&self, target: &str, fragment: &str) -> Option<String> {
+        self(target, fragment)
+    }
+}
+
+impl fmt::Debug for dyn WikiResolver + Send + Sync + RefUnwindSafe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WikiResolver")
+    }
+}